@@ -0,0 +1,203 @@
+//! arch/info.rs
+//!
+//!     Describes a tracee stopped at a syscall, as reported by
+//!     PTRACE_GET_SYSCALL_INFO, which retrieves everything known
+//!     about the syscall in a single call and tells SYS_ENTER and
+//!     SYS_EXIT apart. Kernels older than 5.3 lack the request,
+//!     in which case the same is derived from a register snapshot.
+
+use arch::{Arch, X32_SYSCALL_BIT};
+use arch::regs::Regs;
+
+
+/// `op` values of `struct ptrace_syscall_info`
+const PTRACE_SYSCALL_INFO_ENTRY: u8 = 1;
+const PTRACE_SYSCALL_INFO_EXIT: u8 = 2;
+
+/// `arch` values of `struct ptrace_syscall_info`, as defined in linux/audit.h
+const AUDIT_ARCH_X86_64: u32 = 0xc000_003e;
+const AUDIT_ARCH_I386: u32 = 0x4000_0003;
+const AUDIT_ARCH_AARCH64: u32 = 0xc000_00b7;
+const AUDIT_ARCH_RISCV64: u32 = 0xc000_00f3;
+
+
+/// Defines a syscall-stop of a tracee, either on SYS_ENTER, with the syscall and its
+/// arguments, or on SYS_EXIT, with its return value.
+#[derive(Clone, Copy, Debug)]
+pub enum SyscallInfo {
+    Entry {
+        arch: Arch,
+        ip: u64,
+        num: u64,
+        args: [u64; 6],
+    },
+    Exit {
+        retval: i64,
+        is_error: bool,
+    },
+}
+
+
+impl SyscallInfo {
+
+    /// `from_bytes()` decodes a `struct ptrace_syscall_info` as filled in by the kernel, which
+    /// is laid out the same way on every architecture, in the byte order of the host. Returns `None` for stops other than
+    /// SYS_ENTER and SYS_EXIT, or if too little of the struct was filled in.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let u32_at = |off: usize| {
+            bytes.get(off..off + 4).map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+        };
+        let u64_at = |off: usize| {
+            bytes.get(off..off + 8).map(|b| {
+                let mut word = [0; 8];
+                word.copy_from_slice(b);
+                u64::from_ne_bytes(word)
+            })
+        };
+
+        let op = *bytes.first()?;
+        let arch = from_audit(u32_at(4)?)?;
+        let ip = u64_at(8)?;
+        match op {
+            PTRACE_SYSCALL_INFO_ENTRY => {
+                let num = u64_at(24)?;
+                let mut args = [0; 6];
+                for (idx, arg) in args.iter_mut().enumerate() {
+                    *arg = u64_at(32 + idx * 8)?;
+                }
+                let arch = match arch {
+                    Arch::X86_64 if num & X32_SYSCALL_BIT != 0 => Arch::X32,
+                    arch => arch,
+                };
+                Some(SyscallInfo::Entry { arch, ip, num, args })
+            },
+            PTRACE_SYSCALL_INFO_EXIT => {
+                let retval = u64_at(24)? as i64;
+                let is_error = *bytes.get(32)? != 0;
+                Some(SyscallInfo::Exit { retval, is_error })
+            },
+            _ => None,
+        }
+    }
+
+
+    /// `from_regs()` derives the same from a register snapshot, for kernels lacking
+    /// PTRACE_GET_SYSCALL_INFO. Whether the stop is a SYS_ENTER must be given where the
+    /// architecture cannot tell, and errors are recognized by their return value alone.
+    pub fn from_regs(regs: &Regs, entry: bool) -> Self {
+        if regs.is_entry().unwrap_or(entry) {
+            let mut args = [0; 6];
+            for (idx, arg) in args.iter_mut().enumerate() {
+                *arg = regs.arg(idx);
+            }
+            SyscallInfo::Entry { arch: regs.arch(), ip: regs.ip(), num: regs.syscall_num(), args }
+        } else {
+            let retval = regs.retval();
+            SyscallInfo::Exit { retval, is_error: (-4095..0).contains(&retval) }
+        }
+    }
+}
+
+
+/// `from_audit()` resolves the architecture reported by the kernel. Syscalls made through
+/// the x32 ABI are reported as x86_64, and are told apart by their number instead.
+fn from_audit(audit: u32) -> Option<Arch> {
+    match audit {
+        AUDIT_ARCH_X86_64 => Some(Arch::X86_64),
+        AUDIT_ARCH_I386 => Some(Arch::I386),
+        AUDIT_ARCH_AARCH64 => Some(Arch::Aarch64),
+        AUDIT_ARCH_RISCV64 => Some(Arch::Riscv64),
+        _ => None,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arch::regs::{Aarch64Regs, X86_64Regs};
+
+
+    /// `syscall_info()` builds a `struct ptrace_syscall_info` as filled in by the kernel,
+    /// with the given op and architecture, followed by the words of the entry or exit union.
+    fn syscall_info(op: u8, arch: u32, ip: u64, words: &[u64]) -> Vec<u8> {
+        let mut bytes = vec![0; 88];
+        bytes[0] = op;
+        bytes[4..8].copy_from_slice(&arch.to_ne_bytes());
+        bytes[8..16].copy_from_slice(&ip.to_ne_bytes());
+        bytes[16..24].copy_from_slice(&0x7ffd_5d1e_0000u64.to_ne_bytes());
+        for (idx, word) in words.iter().enumerate() {
+            bytes[24 + idx * 8..32 + idx * 8].copy_from_slice(&word.to_ne_bytes());
+        }
+        bytes
+    }
+
+
+    #[test]
+    fn entry_from_bytes() {
+        let bytes = syscall_info(1, AUDIT_ARCH_X86_64, 0x7f3a_19e2_1a5d,
+                                 &[257, -100i64 as u64, 0x7ffd_5d1e_0a10, 0x80000, 0, 0, 0]);
+        match SyscallInfo::from_bytes(&bytes) {
+            Some(SyscallInfo::Entry { arch, ip, num, args }) => {
+                assert_eq!(arch, Arch::X86_64);
+                assert_eq!(ip, 0x7f3a_19e2_1a5d);
+                assert_eq!(num, 257);
+                assert_eq!(args, [-100i64 as u64, 0x7ffd_5d1e_0a10, 0x80000, 0, 0, 0]);
+            },
+            info => panic!("unexpected {:?}", info),
+        }
+
+        // the architecture is reported as i386 for compat tasks, and x86_64 for x32 tasks
+        let bytes = syscall_info(1, AUDIT_ARCH_I386, 0xf7f4_c549, &[4, 1, 0x0804_a000, 6]);
+        assert!(matches!(SyscallInfo::from_bytes(&bytes), Some(SyscallInfo::Entry { arch: Arch::I386, num: 4, .. })));
+        let bytes = syscall_info(1, AUDIT_ARCH_X86_64, 0, &[X32_SYSCALL_BIT | 1]);
+        assert!(matches!(SyscallInfo::from_bytes(&bytes), Some(SyscallInfo::Entry { arch: Arch::X32, .. })));
+        let bytes = syscall_info(1, AUDIT_ARCH_AARCH64, 0, &[56]);
+        assert!(matches!(SyscallInfo::from_bytes(&bytes), Some(SyscallInfo::Entry { arch: Arch::Aarch64, num: 56, .. })));
+    }
+
+
+    #[test]
+    fn exit_from_bytes() {
+        let bytes = syscall_info(2, AUDIT_ARCH_X86_64, 0, &[3, 0]);
+        assert!(matches!(SyscallInfo::from_bytes(&bytes), Some(SyscallInfo::Exit { retval: 3, is_error: false })));
+        let bytes = syscall_info(2, AUDIT_ARCH_X86_64, 0, &[-2i64 as u64, 1]);
+        assert!(matches!(SyscallInfo::from_bytes(&bytes), Some(SyscallInfo::Exit { retval: -2, is_error: true })));
+    }
+
+
+    #[test]
+    fn other_stops_from_bytes() {
+
+        // PTRACE_SYSCALL_INFO_NONE, an unknown architecture, and a truncated struct
+        assert!(SyscallInfo::from_bytes(&syscall_info(0, AUDIT_ARCH_X86_64, 0, &[])).is_none());
+        assert!(SyscallInfo::from_bytes(&syscall_info(1, 0x4000_0028, 0, &[1])).is_none());
+        assert!(SyscallInfo::from_bytes(&syscall_info(1, AUDIT_ARCH_X86_64, 0, &[1])[..40]).is_none());
+    }
+
+
+    #[test]
+    fn from_x86_64_regs() {
+        let regs = Regs::X86_64(X86_64Regs { orig_rax: 257, rdi: -100i64 as u64, rax: -38i64 as u64, ..Default::default() });
+        assert!(matches!(SyscallInfo::from_regs(&regs, true), SyscallInfo::Entry { arch: Arch::X86_64, num: 257, .. }));
+
+        // x86 cannot tell the direction, so it is taken as given
+        assert!(matches!(SyscallInfo::from_regs(&regs, false), SyscallInfo::Exit { retval: -38, is_error: true }));
+    }
+
+
+    #[test]
+    fn from_aarch64_regs() {
+        let mut regs = Aarch64Regs::default();
+        regs.regs[8] = 56;
+        regs.regs[0] = -100i64 as u64;
+        match SyscallInfo::from_regs(&Regs::Aarch64(regs), false) {
+            SyscallInfo::Entry { num, args, .. } => assert_eq!((num, args[0] as i64), (56, -100)),
+            info => panic!("unexpected {:?}", info),
+        }
+
+        regs.regs[7] = 1;
+        regs.regs[0] = 3;
+        assert!(matches!(SyscallInfo::from_regs(&Regs::Aarch64(regs), true), SyscallInfo::Exit { retval: 3, is_error: false }));
+    }
+}
@@ -0,0 +1,132 @@
+//! arch/mod.rs
+//!
+//!     Syscall tables for every supported architecture, compiled
+//!     into the binary such that no kernel headers are needed on
+//!     the host. The tables map numbers to names, while arity and
+//!     argument types are looked up by name in `sysent` and `args`.
+//!     Register layouts and calling conventions are defined in
+//!     `regs`, and syscall-stops as reported by the kernel in `info`.
+
+use serde::{Serialize, Serializer};
+
+pub mod regs;
+pub mod info;
+
+mod x86_64;
+mod i386;
+mod x32;
+mod aarch64;
+mod riscv64;
+
+
+/// set in the syscall number of every syscall made through the x32 ABI
+pub const X32_SYSCALL_BIT: u64 = 0x4000_0000;
+
+
+/// Defines an architecture, or ABI, that a tracee can make syscalls through. Which
+/// of these are used depends on the architecture jtrace is built for.
+#[allow(dead_code)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Arch {
+    X86_64,
+    I386,
+    X32,
+    Aarch64,
+    Riscv64,
+}
+
+
+impl Arch {
+
+    /// `native()` returns the architecture that jtrace itself was built for.
+    pub fn native() -> Self {
+        if cfg!(all(target_arch = "x86_64", target_pointer_width = "32")) {
+            Arch::X32
+        } else if cfg!(target_arch = "x86_64") {
+            Arch::X86_64
+        } else if cfg!(target_arch = "x86") {
+            Arch::I386
+        } else if cfg!(target_arch = "aarch64") {
+            Arch::Aarch64
+        } else if cfg!(target_arch = "riscv64") {
+            Arch::Riscv64
+        } else {
+            panic!("unsupported architecture");
+        }
+    }
+
+
+    /// `name()` returns the name of the architecture as used by the kernel.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Arch::X86_64 => "x86_64",
+            Arch::I386 => "i386",
+            Arch::X32 => "x32",
+            Arch::Aarch64 => "aarch64",
+            Arch::Riscv64 => "riscv64",
+        }
+    }
+
+
+    /// `table()` returns the syscall numbers and names for the architecture.
+    fn table(&self) -> &'static [(u64, &'static str)] {
+        match self {
+            Arch::X86_64 => x86_64::SYSCALLS,
+            Arch::I386 => i386::SYSCALLS,
+            Arch::X32 => x32::SYSCALLS,
+            Arch::Aarch64 => aarch64::SYSCALLS,
+            Arch::Riscv64 => riscv64::SYSCALLS,
+        }
+    }
+
+
+    /// `syscall_name()` resolves a syscall number into its name, if known.
+    pub fn syscall_name(&self, num: u64) -> Option<&'static str> {
+        let num = match self {
+            Arch::X32 => num & !X32_SYSCALL_BIT,
+            _ => num,
+        };
+        let table = self.table();
+        table.binary_search_by_key(&num, |&(n, _)| n)
+             .ok()
+             .map(|idx| table[idx].1)
+    }
+}
+
+
+impl Serialize for Arch {
+
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    #[test]
+    fn syscall_names() {
+        assert_eq!(Arch::X86_64.syscall_name(1), Some("write"));
+        assert_eq!(Arch::X86_64.syscall_name(257), Some("openat"));
+        assert_eq!(Arch::I386.syscall_name(4), Some("write"));
+        assert_eq!(Arch::I386.syscall_name(295), Some("openat"));
+        assert_eq!(Arch::X32.syscall_name(X32_SYSCALL_BIT | 1), Some("write"));
+        assert_eq!(Arch::X32.syscall_name(X32_SYSCALL_BIT | 520), Some("execve"));
+        assert_eq!(Arch::Aarch64.syscall_name(56), Some("openat"));
+        assert_eq!(Arch::Aarch64.syscall_name(221), Some("execve"));
+        assert_eq!(Arch::Riscv64.syscall_name(64), Some("write"));
+        assert_eq!(Arch::X86_64.syscall_name(0x1c3f), None);
+    }
+
+
+    #[test]
+    fn tables_are_sorted() {
+        for arch in &[Arch::X86_64, Arch::I386, Arch::X32, Arch::Aarch64, Arch::Riscv64] {
+            let table = arch.table();
+            assert!(table.windows(2).all(|pair| pair[0].0 < pair[1].0), "{} table is not sorted", arch.name());
+        }
+    }
+}
@@ -0,0 +1,460 @@
+//! arch/regs.rs
+//!
+//!     Register snapshots of a tracee stopped at a syscall, in the
+//!     layout of the NT_PRSTATUS register set of each architecture,
+//!     and the syscall calling convention used with each of them.
+//!
+//!     Snapshots are decoded from raw bytes independently of the
+//!     host, such that registers recorded on one architecture can
+//!     be decoded on another. Every supported architecture is
+//!     little-endian.
+
+use std::mem;
+
+use serde::Serialize;
+
+use arch::{Arch, X32_SYSCALL_BIT};
+
+
+/// code segment selector of 64-bit tasks on x86_64, as opposed to that of 32-bit ones
+const USER_CS: u64 = 0x33;
+
+/// encoding of the `int 0x80` instruction, which makes a syscall through the i386 ABI
+const INT_80: [u8; 2] = [0xcd, 0x80];
+
+
+/// General-purpose registers of an x86_64 tracee, as in `user_regs_struct`.
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub struct X86_64Regs {
+    pub r15: u64,
+    pub r14: u64,
+    pub r13: u64,
+    pub r12: u64,
+    pub rbp: u64,
+    pub rbx: u64,
+    pub r11: u64,
+    pub r10: u64,
+    pub r9: u64,
+    pub r8: u64,
+    pub rax: u64,
+    pub rcx: u64,
+    pub rdx: u64,
+    pub rsi: u64,
+    pub rdi: u64,
+    pub orig_rax: u64,
+    pub rip: u64,
+    pub cs: u64,
+    pub eflags: u64,
+    pub rsp: u64,
+    pub ss: u64,
+    pub fs_base: u64,
+    pub gs_base: u64,
+    pub ds: u64,
+    pub es: u64,
+    pub fs: u64,
+    pub gs: u64,
+}
+
+
+/// General-purpose registers of an i386 tracee, as in `user_regs_struct`. This is
+/// also the layout reported for 32-bit compat tasks on x86_64.
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub struct I386Regs {
+    pub ebx: u32,
+    pub ecx: u32,
+    pub edx: u32,
+    pub esi: u32,
+    pub edi: u32,
+    pub ebp: u32,
+    pub eax: u32,
+    pub xds: u32,
+    pub xes: u32,
+    pub xfs: u32,
+    pub xgs: u32,
+    pub orig_eax: u32,
+    pub eip: u32,
+    pub xcs: u32,
+    pub eflags: u32,
+    pub esp: u32,
+    pub xss: u32,
+}
+
+
+/// General-purpose registers of an aarch64 tracee, as in `user_pt_regs`.
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub struct Aarch64Regs {
+    pub regs: [u64; 31],
+    pub sp: u64,
+    pub pc: u64,
+    pub pstate: u64,
+}
+
+
+/// General-purpose registers of a riscv64 tracee, as in `user_regs_struct`.
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub struct Riscv64Regs {
+    pub pc: u64,
+    pub ra: u64,
+    pub sp: u64,
+    pub gp: u64,
+    pub tp: u64,
+    pub t0: u64,
+    pub t1: u64,
+    pub t2: u64,
+    pub s0: u64,
+    pub s1: u64,
+    pub a0: u64,
+    pub a1: u64,
+    pub a2: u64,
+    pub a3: u64,
+    pub a4: u64,
+    pub a5: u64,
+    pub a6: u64,
+    pub a7: u64,
+    pub s2: u64,
+    pub s3: u64,
+    pub s4: u64,
+    pub s5: u64,
+    pub s6: u64,
+    pub s7: u64,
+    pub s8: u64,
+    pub s9: u64,
+    pub s10: u64,
+    pub s11: u64,
+    pub t3: u64,
+    pub t4: u64,
+    pub t5: u64,
+    pub t6: u64,
+}
+
+
+/// Defines a snapshot of the registers of a tracee stopped at a syscall, serialized
+/// as the registers themselves, keyed by name.
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(untagged)]
+pub enum Regs {
+    X86_64(X86_64Regs),
+    I386(I386Regs),
+    Aarch64(Aarch64Regs),
+    Riscv64(Riscv64Regs),
+}
+
+
+impl Regs {
+
+    /// `from_bytes()` decodes an NT_PRSTATUS register set retrieved from a tracee by a tracer
+    /// running on the given architecture. On x86_64, the kernel reports the i386 layout for
+    /// 32-bit compat tasks, which is told apart by its size. Returns `None` if the size does
+    /// not match any layout for the architecture.
+    pub fn from_bytes(native: Arch, bytes: &[u8]) -> Option<Self> {
+        let len = bytes.len();
+        match native {
+            Arch::X86_64 | Arch::X32 if len == mem::size_of::<X86_64Regs>() => {
+                let mut w = words64(bytes).into_iter();
+                let mut next = || w.next().unwrap_or(0);
+                Some(Regs::X86_64(X86_64Regs {
+                    r15: next(), r14: next(), r13: next(), r12: next(), rbp: next(), rbx: next(),
+                    r11: next(), r10: next(), r9: next(), r8: next(), rax: next(), rcx: next(),
+                    rdx: next(), rsi: next(), rdi: next(), orig_rax: next(), rip: next(), cs: next(),
+                    eflags: next(), rsp: next(), ss: next(), fs_base: next(), gs_base: next(),
+                    ds: next(), es: next(), fs: next(), gs: next(),
+                }))
+            },
+            Arch::X86_64 | Arch::X32 | Arch::I386 if len == mem::size_of::<I386Regs>() => {
+                let mut w = words32(bytes).into_iter();
+                let mut next = || w.next().unwrap_or(0);
+                Some(Regs::I386(I386Regs {
+                    ebx: next(), ecx: next(), edx: next(), esi: next(), edi: next(), ebp: next(),
+                    eax: next(), xds: next(), xes: next(), xfs: next(), xgs: next(), orig_eax: next(),
+                    eip: next(), xcs: next(), eflags: next(), esp: next(), xss: next(),
+                }))
+            },
+            Arch::Aarch64 if len == mem::size_of::<Aarch64Regs>() => {
+                let mut w = words64(bytes).into_iter();
+                let mut next = || w.next().unwrap_or(0);
+                let mut regs = [0; 31];
+                for reg in regs.iter_mut() {
+                    *reg = next();
+                }
+                Some(Regs::Aarch64(Aarch64Regs { regs, sp: next(), pc: next(), pstate: next() }))
+            },
+            Arch::Riscv64 if len == mem::size_of::<Riscv64Regs>() => {
+                let mut w = words64(bytes).into_iter();
+                let mut next = || w.next().unwrap_or(0);
+                Some(Regs::Riscv64(Riscv64Regs {
+                    pc: next(), ra: next(), sp: next(), gp: next(), tp: next(), t0: next(),
+                    t1: next(), t2: next(), s0: next(), s1: next(), a0: next(), a1: next(),
+                    a2: next(), a3: next(), a4: next(), a5: next(), a6: next(), a7: next(),
+                    s2: next(), s3: next(), s4: next(), s5: next(), s6: next(), s7: next(),
+                    s8: next(), s9: next(), s10: next(), s11: next(), t3: next(), t4: next(),
+                    t5: next(), t6: next(),
+                }))
+            },
+            _ => None,
+        }
+    }
+
+
+    /// `compat()` returns the i386 view of a snapshot of a 64-bit x86_64 task that made
+    /// its syscall with `int 0x80`, given the two bytes preceding the instruction pointer.
+    /// Such syscalls go through the i386 ABI and table, taking their number and arguments
+    /// from the lower halves of the registers, while the register set is that of x86_64.
+    /// Returns `None` for any other snapshot.
+    pub fn compat(&self, insn: &[u8]) -> Option<Self> {
+        match self {
+            Regs::X86_64(regs) if regs.cs == USER_CS && insn == INT_80 => Some(Regs::I386(I386Regs {
+                ebx: regs.rbx as u32, ecx: regs.rcx as u32, edx: regs.rdx as u32, esi: regs.rsi as u32,
+                edi: regs.rdi as u32, ebp: regs.rbp as u32, eax: regs.rax as u32, xds: regs.ds as u32,
+                xes: regs.es as u32, xfs: regs.fs as u32, xgs: regs.gs as u32, orig_eax: regs.orig_rax as u32,
+                eip: regs.rip as u32, xcs: regs.cs as u32, eflags: regs.eflags as u32, esp: regs.rsp as u32,
+                xss: regs.ss as u32,
+            })),
+            _ => None,
+        }
+    }
+
+
+    /// `arch()` returns the architecture the syscall is being made through. Syscalls
+    /// made through the x32 ABI have a bit set in their number.
+    pub fn arch(&self) -> Arch {
+        match self {
+            Regs::X86_64(regs) if regs.orig_rax & X32_SYSCALL_BIT != 0 => Arch::X32,
+            Regs::X86_64(_) => Arch::X86_64,
+            Regs::I386(_) => Arch::I386,
+            Regs::Aarch64(_) => Arch::Aarch64,
+            Regs::Riscv64(_) => Arch::Riscv64,
+        }
+    }
+
+
+    /// `syscall_num()` returns the number of the syscall, which x86 keeps apart from the
+    /// return value register, and aarch64 and riscv64 pass in x8 and a7 respectively.
+    pub fn syscall_num(&self) -> u64 {
+        match self {
+            Regs::X86_64(regs) => regs.orig_rax,
+            Regs::I386(regs) => u64::from(regs.orig_eax),
+            Regs::Aarch64(regs) => regs.regs[8],
+            Regs::Riscv64(regs) => regs.a7,
+        }
+    }
+
+
+    /// `ip()` returns the instruction pointer, which is just past the syscall instruction.
+    pub fn ip(&self) -> u64 {
+        match self {
+            Regs::X86_64(regs) => regs.rip,
+            Regs::I386(regs) => u64::from(regs.eip),
+            Regs::Aarch64(regs) => regs.pc,
+            Regs::Riscv64(regs) => regs.pc,
+        }
+    }
+
+
+    /// `arg()` returns an argument of the syscall by index. Note that x86_64 passes the
+    /// 4th argument in R10 rather than RCX, which `syscall` clobbers, and that on aarch64
+    /// and riscv64 the first argument is overwritten by the return value, so arguments
+    /// can only be read on SYS_ENTER.
+    pub fn arg(&self, idx: usize) -> u64 {
+        match self {
+            Regs::X86_64(regs) => match idx {
+                0 => regs.rdi,
+                1 => regs.rsi,
+                2 => regs.rdx,
+                3 => regs.r10,
+                4 => regs.r8,
+                5 => regs.r9,
+                _ => panic!("Unmatched argument offset")
+            },
+            Regs::I386(regs) => u64::from(match idx {
+                0 => regs.ebx,
+                1 => regs.ecx,
+                2 => regs.edx,
+                3 => regs.esi,
+                4 => regs.edi,
+                5 => regs.ebp,
+                _ => panic!("Unmatched argument offset")
+            }),
+            Regs::Aarch64(regs) => match idx {
+                0..=5 => regs.regs[idx],
+                _ => panic!("Unmatched argument offset")
+            },
+            Regs::Riscv64(regs) => match idx {
+                0 => regs.a0,
+                1 => regs.a1,
+                2 => regs.a2,
+                3 => regs.a3,
+                4 => regs.a4,
+                5 => regs.a5,
+                _ => panic!("Unmatched argument offset")
+            },
+        }
+    }
+
+
+    /// `retval()` returns the return value of the syscall on SYS_EXIT, sign-extended
+    /// for 32-bit tasks.
+    pub fn retval(&self) -> i64 {
+        match self {
+            Regs::X86_64(regs) => regs.rax as i64,
+            Regs::I386(regs) => i64::from(regs.eax as i32),
+            Regs::Aarch64(regs) => regs.regs[0] as i64,
+            Regs::Riscv64(regs) => regs.a0 as i64,
+        }
+    }
+
+
+    /// `is_entry()` reports whether the stop is a SYS_ENTER, if the architecture tells.
+    /// On aarch64, the kernel sets x7 to 0 on SYS_ENTER and to 1 on SYS_EXIT for the
+    /// tracer to see, restoring it once the tracee is resumed.
+    pub fn is_entry(&self) -> Option<bool> {
+        match self {
+            Regs::Aarch64(regs) => Some(regs.regs[7] == 0),
+            _ => None,
+        }
+    }
+}
+
+
+/// `words64()` splits raw register bytes into little-endian 64-bit words.
+fn words64(bytes: &[u8]) -> Vec<u64> {
+    bytes.chunks(8)
+         .map(|chunk| {
+             let mut word = [0; 8];
+             word[..chunk.len()].copy_from_slice(chunk);
+             u64::from_le_bytes(word)
+         })
+         .collect()
+}
+
+
+/// `words32()` splits raw register bytes into little-endian 32-bit words.
+fn words32(bytes: &[u8]) -> Vec<u32> {
+    bytes.chunks(4)
+         .map(|chunk| {
+             let mut word = [0; 4];
+             word[..chunk.len()].copy_from_slice(chunk);
+             u32::from_le_bytes(word)
+         })
+         .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    /// `snapshot()` builds a raw register set of `len` words of `width` bytes, all zero
+    /// but for the given words, as it would be retrieved with PTRACE_GETREGSET.
+    fn snapshot(width: usize, len: usize, words: &[(usize, u64)]) -> Vec<u8> {
+        let mut bytes = vec![0; width * len];
+        for &(idx, value) in words {
+            bytes[idx * width..(idx + 1) * width].copy_from_slice(&value.to_le_bytes()[..width]);
+        }
+        bytes
+    }
+
+
+    #[test]
+    fn x86_64_snapshot() {
+
+        // openat(AT_FDCWD, 0x7ffd5d1e0a10, O_RDONLY|O_CLOEXEC) in orig_rax, rdi, rsi and rdx,
+        // with rax holding -ENOSYS on SYS_ENTER
+        let entry = snapshot(8, 27, &[(15, 257), (14, -100i64 as u64), (13, 0x7ffd_5d1e_0a10),
+                                     (12, 0x80000), (10, -38i64 as u64), (16, 0x7f3a_19e2_1a5d), (17, 0x33)]);
+        let regs = Regs::from_bytes(Arch::X86_64, &entry).unwrap();
+        assert_eq!(regs.arch(), Arch::X86_64);
+        assert_eq!(regs.syscall_num(), 257);
+        assert_eq!(regs.arg(0) as i64, -100);
+        assert_eq!(regs.arg(1), 0x7ffd_5d1e_0a10);
+        assert_eq!(regs.arg(2), 0x80000);
+        assert_eq!(regs.ip(), 0x7f3a_19e2_1a5d);
+        assert_eq!(regs.is_entry(), None);
+
+        let exit = snapshot(8, 27, &[(15, 257), (10, 3)]);
+        assert_eq!(Regs::from_bytes(Arch::X86_64, &exit).unwrap().retval(), 3);
+
+        // the 4th argument is passed in r10 rather than rcx
+        let mmap = snapshot(8, 27, &[(15, 9), (7, 0x22), (11, 0xdead)]);
+        assert_eq!(Regs::from_bytes(Arch::X86_64, &mmap).unwrap().arg(3), 0x22);
+
+        // syscalls made through the x32 ABI have a bit set in their number
+        let x32 = snapshot(8, 27, &[(15, X32_SYSCALL_BIT | 1)]);
+        assert_eq!(Regs::from_bytes(Arch::X86_64, &x32).unwrap().arch(), Arch::X32);
+    }
+
+
+    #[test]
+    fn i386_snapshot_on_x86_64() {
+
+        // write(1, 0x804a000, 6) from a 32-bit compat task, reported in the smaller layout
+        let entry = snapshot(4, 17, &[(11, 4), (0, 1), (1, 0x0804_a000), (2, 6), (12, 0xf7f4_c549), (13, 0x23)]);
+        let regs = Regs::from_bytes(Arch::X86_64, &entry).unwrap();
+        assert_eq!(regs.arch(), Arch::I386);
+        assert_eq!(regs.syscall_num(), 4);
+        assert_eq!((regs.arg(0), regs.arg(1), regs.arg(2)), (1, 0x0804_a000, 6));
+        assert_eq!(regs.ip(), 0xf7f4_c549);
+
+        // return values are sign-extended from eax
+        let exit = snapshot(4, 17, &[(11, 4), (6, 0xffff_fff2)]);
+        assert_eq!(Regs::from_bytes(Arch::X86_64, &exit).unwrap().retval(), -14);
+    }
+
+
+    #[test]
+    fn aarch64_snapshot() {
+
+        // openat(AT_FDCWD, 0xffffe5c0b2e0, O_RDONLY) in x8 and x0-x2, with x7 cleared on SYS_ENTER
+        let entry = snapshot(8, 34, &[(8, 56), (0, -100i64 as u64), (1, 0xffff_e5c0_b2e0), (7, 0), (32, 0xffff_9a3c_1e2c)]);
+        let regs = Regs::from_bytes(Arch::Aarch64, &entry).unwrap();
+        assert_eq!(regs.arch(), Arch::Aarch64);
+        assert_eq!(regs.is_entry(), Some(true));
+        assert_eq!(regs.syscall_num(), 56);
+        assert_eq!(regs.arg(0) as i64, -100);
+        assert_eq!(regs.arg(1), 0xffff_e5c0_b2e0);
+        assert_eq!(regs.ip(), 0xffff_9a3c_1e2c);
+
+        // x0 is clobbered by the return value, and x7 is set on SYS_EXIT
+        let exit = snapshot(8, 34, &[(8, 56), (0, -2i64 as u64), (7, 1)]);
+        let regs = Regs::from_bytes(Arch::Aarch64, &exit).unwrap();
+        assert_eq!(regs.is_entry(), Some(false));
+        assert_eq!(regs.retval(), -2);
+    }
+
+
+    #[test]
+    fn riscv64_snapshot() {
+        let entry = snapshot(8, 32, &[(17, 64), (10, 1), (11, 0x3f_f7a2_1000), (12, 5), (0, 0x3f_f7b0_0c48)]);
+        let regs = Regs::from_bytes(Arch::Riscv64, &entry).unwrap();
+        assert_eq!(regs.syscall_num(), 64);
+        assert_eq!((regs.arg(0), regs.arg(1), regs.arg(2)), (1, 0x3f_f7a2_1000, 5));
+        assert_eq!(regs.ip(), 0x3f_f7b0_0c48);
+    }
+
+
+    #[test]
+    fn unexpected_sizes() {
+        assert!(Regs::from_bytes(Arch::X86_64, &[0; 100]).is_none());
+        assert!(Regs::from_bytes(Arch::Aarch64, &snapshot(4, 17, &[])).is_none());
+        assert!(Regs::from_bytes(Arch::I386, &snapshot(8, 27, &[])).is_none());
+    }
+
+
+    #[test]
+    fn int80_from_64bit_task() {
+        let regs = Regs::X86_64(X86_64Regs {
+            orig_rax: 4, rbx: 1, rcx: 0x0804_a000, rdx: 6, rax: 0xffff_ffff_ffff_ffda, cs: USER_CS,
+            ..Default::default()
+        });
+        let compat = regs.compat(&INT_80).unwrap();
+        assert_eq!(compat.arch(), Arch::I386);
+        assert_eq!(compat.syscall_num(), 4);
+        assert_eq!((compat.arg(0), compat.arg(1), compat.arg(2)), (1, 0x0804_a000, 6));
+        assert_eq!(compat.retval(), -38);
+
+        // `syscall` is encoded as 0f 05, and 32-bit tasks are already reported as i386
+        assert!(regs.compat(&[0x0f, 0x05]).is_none());
+        let regs = Regs::X86_64(X86_64Regs { cs: 0x23, ..Default::default() });
+        assert!(regs.compat(&INT_80).is_none());
+    }
+}
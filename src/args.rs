@@ -0,0 +1,863 @@
+//! args.rs
+//!
+//!     Typed syscall arguments, keyed by syscall name like the
+//!     metadata in `sysent`, and their symbolic rendering, such
+//!     that `openat` flags are shown as `O_RDONLY|O_CLOEXEC`
+//!     rather than as `524288`.
+//!
+//!     Constants are taken from `libc` where it defines them for
+//!     every Linux target, as some of them differ between
+//!     architectures, and spelled out otherwise.
+
+use std::collections::{BTreeMap, HashMap};
+
+use arch::Arch;
+use event;
+use structs::{StructType, Dir, Count};
+
+
+/// Describes how an argument is interpreted and rendered.
+#[derive(Clone, Copy)]
+pub enum ArgType {
+
+    /// a number of unknown type, rendered as is
+    Num,
+
+    /// a signed int
+    Int,
+
+    /// a signed long, such as a file offset
+    Long,
+
+    /// a 64-bit file offset or length, which i386 tasks pass in a pair of registers,
+    /// see `sysent::split_args()`
+    Loff,
+
+    /// an unsigned size or count
+    Size,
+
+    /// a file descriptor
+    Fd,
+
+    /// a file descriptor that paths are resolved relative to, which may be AT_FDCWD
+    DirFd,
+
+    /// an address in tracee memory, rendered in hex or as NULL
+    Ptr,
+
+    /// a string in tracee memory, as listed in `sysent::string_args()`
+    Str,
+
+    /// file permission bits in octal, along with the file type if any
+    Mode,
+
+    /// a bitmask, optionally combined with a constant in its lower bits
+    Flags(&'static Flags),
+
+    /// one of a set of constants
+    Enum(&'static [(u64, &'static str)]),
+
+    /// a signal number
+    Signal,
+
+    /// a pointer to structures decoded from tracee memory, see `structs`
+    Struct(StructType, Dir, Count),
+
+    /// a pointer to data transferred by an I/O syscall, whose length is given by
+    /// another argument or by the return value
+    Buf(Dir, Count),
+}
+
+
+/// Describes a bitmask argument. Some bitmasks are combined with a constant, such as
+/// the access mode of `open` or the exit signal of `clone`, which covers the bits in
+/// `mask` and is rendered ahead of the individual bits, as in `O_RDONLY|O_CLOEXEC`.
+pub struct Flags {
+    pub base: &'static [(u64, &'static str)],
+    pub mask: u64,
+    pub bits: &'static [(u64, &'static str)],
+}
+
+
+use self::ArgType::*;
+use structs::StructType::*;
+use structs::Dir::*;
+use structs::Count::*;
+
+
+lazy_static! {
+    static ref SIGNATURES_MAP: HashMap<&'static str, &'static [ArgType]> = SIGNATURES
+        .iter()
+        .cloned()
+        .collect();
+}
+
+
+/// `arg_type()` returns the type of an argument of a syscall by index, which is a
+/// plain number if the syscall has no known signature.
+pub fn arg_type(name: &str, idx: usize) -> ArgType {
+    SIGNATURES_MAP.get(name)
+                  .and_then(|types| types.get(idx))
+                  .cloned()
+                  .unwrap_or(Num)
+}
+
+
+/// `structs()` returns the arguments of a syscall that point to structures passed
+/// in the given direction, including those passed both ways, along with how to decode
+/// them and the direction they are declared with.
+pub fn structs(name: &str, dir: Dir) -> Vec<(usize, StructType, Dir, Count)> {
+    let types = match SIGNATURES_MAP.get(name) {
+        Some(types) => types,
+        None => return Vec::new(),
+    };
+    types.iter()
+         .enumerate()
+         .filter_map(|(idx, ty)| match *ty {
+             Struct(ty, d, count) if d == dir || d == InOut => Some((idx, ty, d, count)),
+             _ => None,
+         })
+         .collect()
+}
+
+
+/// `buffers()` returns the arguments of a syscall that point to data transferred in
+/// the given direction, along with where its length is taken from.
+pub fn buffers(name: &str, dir: Dir) -> Vec<(usize, Count)> {
+    let types = match SIGNATURES_MAP.get(name) {
+        Some(types) => types,
+        None => return Vec::new(),
+    };
+    types.iter()
+         .enumerate()
+         .filter_map(|(idx, ty)| match *ty {
+             Buf(d, count) if d == dir => Some((idx, count)),
+             _ => None,
+         })
+         .collect()
+}
+
+
+/// `shown()` returns how many of the arguments of a syscall are rendered. The mode of
+/// `open` and `openat` is only read by the kernel, and shown, along with O_CREAT or
+/// O_TMPFILE, as it is otherwise left over from whatever was in the register.
+pub fn shown(name: &str, args: &[u64]) -> usize {
+    let mode = match name {
+        "open" => 2,
+        "openat" => 3,
+        _ => return args.len(),
+    };
+    let (creat, tmpfile) = (libc::O_CREAT as u64, libc::O_TMPFILE as u64);
+    match args.get(mode - 1) {
+        Some(&flags) if flags & creat == 0 && flags & tmpfile != tmpfile => mode,
+        _ => args.len(),
+    }
+}
+
+
+/// `decode()` renders every argument of a syscall that has a known type, keyed by
+/// argument index. Strings are left out, as they are read from tracee memory, as
+/// are arguments that render the same as their raw value.
+pub fn decode(name: &str, arch: Arch, args: &[u64]) -> BTreeMap<usize, String> {
+    args.iter()
+        .take(shown(name, args))
+        .enumerate()
+        .filter_map(|(idx, &value)| {
+            let text = render(arg_type(name, idx), arch, value)?;
+            if text == value.to_string() {
+                None
+            } else {
+                Some((idx, text))
+            }
+        })
+        .collect()
+}
+
+
+/// `render()` renders a single argument as strace does, or returns `None` for
+/// arguments that are not rendered from their value alone.
+pub fn render(ty: ArgType, arch: Arch, value: u64) -> Option<String> {
+
+    // longs are only 32 bits wide for i386 tasks
+    let long = match arch {
+        Arch::I386 => i64::from(value as i32),
+        _ => value as i64,
+    };
+
+    let text = match ty {
+        Num | Str => return None,
+        Int | Fd => (value as i32).to_string(),
+        Long => long.to_string(),
+        Loff => (value as i64).to_string(),
+        Size => value.to_string(),
+        DirFd if value as i32 == libc::AT_FDCWD => "AT_FDCWD".to_string(),
+        DirFd => (value as i32).to_string(),
+        Ptr | Struct(..) | Buf(..) if value == 0 => "NULL".to_string(),
+        Ptr | Struct(..) | Buf(..) => format!("{:#x}", value),
+        Mode => render_mode(value),
+        Flags(flags) => render_flags(flags, value),
+        Enum(consts) => match lookup(consts, value) {
+            Some(name) => name.to_string(),
+            None => (value as i32).to_string(),
+        },
+        Signal if value == 0 => "0".to_string(),
+        Signal => event::signal_name(value as i32),
+    };
+    Some(text)
+}
+
+
+/// `lookup()` finds the name of a constant by value.
+fn lookup(consts: &[(u64, &'static str)], value: u64) -> Option<&'static str> {
+    consts.iter()
+          .find(|&&(v, _)| v == value)
+          .map(|&(_, name)| name)
+}
+
+
+/// `render_flags()` renders a bitmask as the names of its bits joined by `|`, with any
+/// bits left unnamed in hex, and a zero bitmask as `0` unless a constant names it.
+fn render_flags(flags: &Flags, value: u64) -> String {
+    let mut parts: Vec<String> = Vec::new();
+
+    // a constant for the masked bits, or for the whole value if nothing is masked
+    let base = value & flags.mask;
+    if flags.mask != 0 || value == 0 {
+        match lookup(flags.base, base) {
+            Some(name) => parts.push(name.to_string()),
+            None if base != 0 => parts.push(format!("{:#x}", base)),
+            None => {},
+        }
+    }
+
+    // bits are matched in order, such that flags spanning several bits come first
+    let mut rest = value & !flags.mask;
+    for &(bit, name) in flags.bits {
+        if bit != 0 && rest & bit == bit {
+            parts.push(name.to_string());
+            rest &= !bit;
+        }
+    }
+    if rest != 0 {
+        parts.push(format!("{:#x}", rest));
+    }
+
+    if parts.is_empty() {
+        "0".to_string()
+    } else {
+        parts.join("|")
+    }
+}
+
+
+/// `render_mode()` renders file permission bits in octal, such as `0644`, prefixed
+/// by the file type if any, such as `S_IFCHR|0600`.
+fn render_mode(value: u64) -> String {
+    let perm = format!("0{:02o}", value & 0o7777);
+    match lookup(FILE_TYPES, value & libc::S_IFMT as u64) {
+        Some(name) => format!("{}|{}", name, perm),
+        None => perm,
+    }
+}
+
+
+static FILE_TYPES: &[(u64, &str)] = &[
+    (libc::S_IFSOCK as u64, "S_IFSOCK"),
+    (libc::S_IFLNK as u64,  "S_IFLNK"),
+    (libc::S_IFREG as u64,  "S_IFREG"),
+    (libc::S_IFBLK as u64,  "S_IFBLK"),
+    (libc::S_IFDIR as u64,  "S_IFDIR"),
+    (libc::S_IFCHR as u64,  "S_IFCHR"),
+    (libc::S_IFIFO as u64,  "S_IFIFO"),
+];
+
+
+static OPEN_FLAGS: Flags = Flags {
+    base: &[
+        (libc::O_RDONLY as u64, "O_RDONLY"),
+        (libc::O_WRONLY as u64, "O_WRONLY"),
+        (libc::O_RDWR as u64,   "O_RDWR"),
+    ],
+    mask: libc::O_ACCMODE as u64,
+    bits: &[
+        (libc::O_CREAT as u64,     "O_CREAT"),
+        (libc::O_EXCL as u64,      "O_EXCL"),
+        (libc::O_NOCTTY as u64,    "O_NOCTTY"),
+        (libc::O_TRUNC as u64,     "O_TRUNC"),
+        (libc::O_APPEND as u64,    "O_APPEND"),
+        (libc::O_NONBLOCK as u64,  "O_NONBLOCK"),
+        (libc::O_SYNC as u64,      "O_SYNC"),
+        (libc::O_DSYNC as u64,     "O_DSYNC"),
+        (libc::O_ASYNC as u64,     "O_ASYNC"),
+        (libc::O_DIRECT as u64,    "O_DIRECT"),
+        (libc::O_LARGEFILE as u64, "O_LARGEFILE"),
+        (libc::O_TMPFILE as u64,   "O_TMPFILE"),
+        (libc::O_DIRECTORY as u64, "O_DIRECTORY"),
+        (libc::O_NOFOLLOW as u64,  "O_NOFOLLOW"),
+        (libc::O_NOATIME as u64,   "O_NOATIME"),
+        (libc::O_CLOEXEC as u64,   "O_CLOEXEC"),
+        (libc::O_PATH as u64,      "O_PATH"),
+    ],
+};
+
+
+/// flags of `pipe2`, `dup3` and the like, which take no access mode
+static FD_FLAGS: Flags = Flags {
+    base: &[],
+    mask: 0,
+    bits: &[
+        (libc::O_NONBLOCK as u64, "O_NONBLOCK"),
+        (libc::O_DIRECT as u64,   "O_DIRECT"),
+        (libc::O_CLOEXEC as u64,  "O_CLOEXEC"),
+    ],
+};
+
+
+static AT_FLAGS: Flags = Flags {
+    base: &[],
+    mask: 0,
+    bits: &[
+        (libc::AT_SYMLINK_NOFOLLOW as u64, "AT_SYMLINK_NOFOLLOW"),
+        (libc::AT_SYMLINK_FOLLOW as u64,   "AT_SYMLINK_FOLLOW"),
+        (libc::AT_NO_AUTOMOUNT as u64,     "AT_NO_AUTOMOUNT"),
+        (libc::AT_EMPTY_PATH as u64,       "AT_EMPTY_PATH"),
+        (0x2000,                           "AT_STATX_FORCE_SYNC"),
+        (0x4000,                           "AT_STATX_DONT_SYNC"),
+    ],
+};
+
+
+static UNLINKAT_FLAGS: Flags = Flags {
+    base: &[],
+    mask: 0,
+    bits: &[(libc::AT_REMOVEDIR as u64, "AT_REMOVEDIR")],
+};
+
+
+static FACCESSAT_FLAGS: Flags = Flags {
+    base: &[],
+    mask: 0,
+    bits: &[
+        (libc::AT_EACCESS as u64,          "AT_EACCESS"),
+        (libc::AT_SYMLINK_NOFOLLOW as u64, "AT_SYMLINK_NOFOLLOW"),
+        (libc::AT_EMPTY_PATH as u64,       "AT_EMPTY_PATH"),
+    ],
+};
+
+
+static ACCESS_MODES: Flags = Flags {
+    base: &[(libc::F_OK as u64, "F_OK")],
+    mask: 0,
+    bits: &[
+        (libc::R_OK as u64, "R_OK"),
+        (libc::W_OK as u64, "W_OK"),
+        (libc::X_OK as u64, "X_OK"),
+    ],
+};
+
+
+static RENAME_FLAGS: Flags = Flags {
+    base: &[],
+    mask: 0,
+    bits: &[
+        (1, "RENAME_NOREPLACE"),
+        (2, "RENAME_EXCHANGE"),
+        (4, "RENAME_WHITEOUT"),
+    ],
+};
+
+
+static SEEK_WHENCE: &[(u64, &str)] = &[
+    (libc::SEEK_SET as u64,  "SEEK_SET"),
+    (libc::SEEK_CUR as u64,  "SEEK_CUR"),
+    (libc::SEEK_END as u64,  "SEEK_END"),
+    (libc::SEEK_DATA as u64, "SEEK_DATA"),
+    (libc::SEEK_HOLE as u64, "SEEK_HOLE"),
+];
+
+
+static FCNTL_CMDS: &[(u64, &str)] = &[
+    (libc::F_DUPFD as u64,         "F_DUPFD"),
+    (libc::F_GETFD as u64,         "F_GETFD"),
+    (libc::F_SETFD as u64,         "F_SETFD"),
+    (libc::F_GETFL as u64,         "F_GETFL"),
+    (libc::F_SETFL as u64,         "F_SETFL"),
+    (libc::F_GETLK as u64,         "F_GETLK"),
+    (libc::F_SETLK as u64,         "F_SETLK"),
+    (libc::F_SETLKW as u64,        "F_SETLKW"),
+    (libc::F_SETOWN as u64,        "F_SETOWN"),
+    (libc::F_GETOWN as u64,        "F_GETOWN"),
+    (libc::F_OFD_GETLK as u64,     "F_OFD_GETLK"),
+    (libc::F_OFD_SETLK as u64,     "F_OFD_SETLK"),
+    (libc::F_OFD_SETLKW as u64,    "F_OFD_SETLKW"),
+    (libc::F_SETLEASE as u64,      "F_SETLEASE"),
+    (libc::F_GETLEASE as u64,      "F_GETLEASE"),
+    (libc::F_NOTIFY as u64,        "F_NOTIFY"),
+    (libc::F_DUPFD_CLOEXEC as u64, "F_DUPFD_CLOEXEC"),
+    (libc::F_SETPIPE_SZ as u64,    "F_SETPIPE_SZ"),
+    (libc::F_GETPIPE_SZ as u64,    "F_GETPIPE_SZ"),
+    (libc::F_ADD_SEALS as u64,     "F_ADD_SEALS"),
+    (libc::F_GET_SEALS as u64,     "F_GET_SEALS"),
+];
+
+
+pub static MMAP_PROT: Flags = Flags {
+    base: &[(libc::PROT_NONE as u64, "PROT_NONE")],
+    mask: 0,
+    bits: &[
+        (libc::PROT_READ as u64,      "PROT_READ"),
+        (libc::PROT_WRITE as u64,     "PROT_WRITE"),
+        (libc::PROT_EXEC as u64,      "PROT_EXEC"),
+        (libc::PROT_GROWSDOWN as u64, "PROT_GROWSDOWN"),
+        (libc::PROT_GROWSUP as u64,   "PROT_GROWSUP"),
+    ],
+};
+
+
+pub static MMAP_FLAGS: Flags = Flags {
+    base: &[
+        (libc::MAP_SHARED as u64,          "MAP_SHARED"),
+        (libc::MAP_PRIVATE as u64,         "MAP_PRIVATE"),
+        (libc::MAP_SHARED_VALIDATE as u64, "MAP_SHARED_VALIDATE"),
+    ],
+    mask: 0x3,
+    bits: &[
+        (libc::MAP_FIXED as u64,           "MAP_FIXED"),
+        (libc::MAP_ANONYMOUS as u64,       "MAP_ANONYMOUS"),
+        (libc::MAP_GROWSDOWN as u64,       "MAP_GROWSDOWN"),
+        (libc::MAP_DENYWRITE as u64,       "MAP_DENYWRITE"),
+        (libc::MAP_EXECUTABLE as u64,      "MAP_EXECUTABLE"),
+        (libc::MAP_LOCKED as u64,          "MAP_LOCKED"),
+        (libc::MAP_NORESERVE as u64,       "MAP_NORESERVE"),
+        (libc::MAP_POPULATE as u64,        "MAP_POPULATE"),
+        (libc::MAP_NONBLOCK as u64,        "MAP_NONBLOCK"),
+        (libc::MAP_STACK as u64,           "MAP_STACK"),
+        (libc::MAP_HUGETLB as u64,         "MAP_HUGETLB"),
+        (libc::MAP_SYNC as u64,            "MAP_SYNC"),
+        (libc::MAP_FIXED_NOREPLACE as u64, "MAP_FIXED_NOREPLACE"),
+    ],
+};
+
+
+static MREMAP_FLAGS: Flags = Flags {
+    base: &[],
+    mask: 0,
+    bits: &[
+        (libc::MREMAP_MAYMOVE as u64, "MREMAP_MAYMOVE"),
+        (libc::MREMAP_FIXED as u64,   "MREMAP_FIXED"),
+        (4,                           "MREMAP_DONTUNMAP"),
+    ],
+};
+
+
+static MADVISE_ADVICE: &[(u64, &str)] = &[
+    (libc::MADV_NORMAL as u64,      "MADV_NORMAL"),
+    (libc::MADV_RANDOM as u64,      "MADV_RANDOM"),
+    (libc::MADV_SEQUENTIAL as u64,  "MADV_SEQUENTIAL"),
+    (libc::MADV_WILLNEED as u64,    "MADV_WILLNEED"),
+    (libc::MADV_DONTNEED as u64,    "MADV_DONTNEED"),
+    (libc::MADV_FREE as u64,        "MADV_FREE"),
+    (libc::MADV_REMOVE as u64,      "MADV_REMOVE"),
+    (libc::MADV_DONTFORK as u64,    "MADV_DONTFORK"),
+    (libc::MADV_DOFORK as u64,      "MADV_DOFORK"),
+    (libc::MADV_MERGEABLE as u64,   "MADV_MERGEABLE"),
+    (libc::MADV_UNMERGEABLE as u64, "MADV_UNMERGEABLE"),
+    (libc::MADV_HUGEPAGE as u64,    "MADV_HUGEPAGE"),
+    (libc::MADV_NOHUGEPAGE as u64,  "MADV_NOHUGEPAGE"),
+    (libc::MADV_DONTDUMP as u64,    "MADV_DONTDUMP"),
+    (libc::MADV_DODUMP as u64,      "MADV_DODUMP"),
+    (libc::MADV_WIPEONFORK as u64,  "MADV_WIPEONFORK"),
+    (libc::MADV_KEEPONFORK as u64,  "MADV_KEEPONFORK"),
+    (20,                            "MADV_COLD"),
+    (21,                            "MADV_PAGEOUT"),
+    (libc::MADV_HWPOISON as u64,    "MADV_HWPOISON"),
+];
+
+
+/// flags of `clone`, of which the lowest byte is the signal sent to the parent on exit
+static CLONE_FLAGS: Flags = Flags {
+    base: &[(libc::SIGCHLD as u64, "SIGCHLD")],
+    mask: 0xff,
+    bits: &[
+        (libc::CLONE_VM as u64,                    "CLONE_VM"),
+        (libc::CLONE_FS as u64,                    "CLONE_FS"),
+        (libc::CLONE_FILES as u64,                 "CLONE_FILES"),
+        (libc::CLONE_SIGHAND as u64,               "CLONE_SIGHAND"),
+        (libc::CLONE_PIDFD as u64,                 "CLONE_PIDFD"),
+        (libc::CLONE_PTRACE as u64,                "CLONE_PTRACE"),
+        (libc::CLONE_VFORK as u64,                 "CLONE_VFORK"),
+        (libc::CLONE_PARENT as u64,                "CLONE_PARENT"),
+        (libc::CLONE_THREAD as u64,                "CLONE_THREAD"),
+        (libc::CLONE_NEWNS as u64,                 "CLONE_NEWNS"),
+        (libc::CLONE_SYSVSEM as u64,               "CLONE_SYSVSEM"),
+        (libc::CLONE_SETTLS as u64,                "CLONE_SETTLS"),
+        (libc::CLONE_PARENT_SETTID as u64,         "CLONE_PARENT_SETTID"),
+        (libc::CLONE_CHILD_CLEARTID as u64,        "CLONE_CHILD_CLEARTID"),
+        (libc::CLONE_DETACHED as u64,              "CLONE_DETACHED"),
+        (libc::CLONE_UNTRACED as u64,              "CLONE_UNTRACED"),
+        (libc::CLONE_CHILD_SETTID as u64,          "CLONE_CHILD_SETTID"),
+        (libc::CLONE_NEWCGROUP as u64,             "CLONE_NEWCGROUP"),
+        (libc::CLONE_NEWUTS as u64,                "CLONE_NEWUTS"),
+        (libc::CLONE_NEWIPC as u64,                "CLONE_NEWIPC"),
+        (libc::CLONE_NEWUSER as u64,               "CLONE_NEWUSER"),
+        (libc::CLONE_NEWPID as u64,                "CLONE_NEWPID"),
+        (libc::CLONE_NEWNET as u64,                "CLONE_NEWNET"),
+        (libc::CLONE_IO as u32 as u64,             "CLONE_IO"),
+    ],
+};
+
+
+static WAIT_OPTIONS: Flags = Flags {
+    base: &[],
+    mask: 0,
+    bits: &[
+        (libc::WNOHANG as u64,             "WNOHANG"),
+        (libc::WUNTRACED as u64,           "WUNTRACED"),
+        (libc::WCONTINUED as u64,          "WCONTINUED"),
+        (libc::__WNOTHREAD as u32 as u64,  "__WNOTHREAD"),
+        (libc::__WALL as u32 as u64,       "__WALL"),
+        (libc::__WCLONE as u32 as u64,     "__WCLONE"),
+    ],
+};
+
+
+static SIGPROCMASK_HOW: &[(u64, &str)] = &[
+    (libc::SIG_BLOCK as u64,   "SIG_BLOCK"),
+    (libc::SIG_UNBLOCK as u64, "SIG_UNBLOCK"),
+    (libc::SIG_SETMASK as u64, "SIG_SETMASK"),
+];
+
+
+static ARCH_PRCTL_CODES: &[(u64, &str)] = &[
+    (0x1001, "ARCH_SET_GS"),
+    (0x1002, "ARCH_SET_FS"),
+    (0x1003, "ARCH_GET_FS"),
+    (0x1004, "ARCH_GET_GS"),
+    (0x1011, "ARCH_GET_CPUID"),
+    (0x1012, "ARCH_SET_CPUID"),
+];
+
+
+static FUTEX_OPS: Flags = Flags {
+    base: &[
+        (0,  "FUTEX_WAIT"),
+        (1,  "FUTEX_WAKE"),
+        (2,  "FUTEX_FD"),
+        (3,  "FUTEX_REQUEUE"),
+        (4,  "FUTEX_CMP_REQUEUE"),
+        (5,  "FUTEX_WAKE_OP"),
+        (6,  "FUTEX_LOCK_PI"),
+        (7,  "FUTEX_UNLOCK_PI"),
+        (8,  "FUTEX_TRYLOCK_PI"),
+        (9,  "FUTEX_WAIT_BITSET"),
+        (10, "FUTEX_WAKE_BITSET"),
+        (11, "FUTEX_WAIT_REQUEUE_PI"),
+        (12, "FUTEX_CMP_REQUEUE_PI"),
+        (13, "FUTEX_LOCK_PI2"),
+    ],
+    mask: 0x7f,
+    bits: &[
+        (128, "FUTEX_PRIVATE_FLAG"),
+        (256, "FUTEX_CLOCK_REALTIME"),
+    ],
+};
+
+
+static RLIMIT_RESOURCES: &[(u64, &str)] = &[
+    (libc::RLIMIT_CPU as u64,        "RLIMIT_CPU"),
+    (libc::RLIMIT_FSIZE as u64,      "RLIMIT_FSIZE"),
+    (libc::RLIMIT_DATA as u64,       "RLIMIT_DATA"),
+    (libc::RLIMIT_STACK as u64,      "RLIMIT_STACK"),
+    (libc::RLIMIT_CORE as u64,       "RLIMIT_CORE"),
+    (libc::RLIMIT_RSS as u64,        "RLIMIT_RSS"),
+    (libc::RLIMIT_NPROC as u64,      "RLIMIT_NPROC"),
+    (libc::RLIMIT_NOFILE as u64,     "RLIMIT_NOFILE"),
+    (libc::RLIMIT_MEMLOCK as u64,    "RLIMIT_MEMLOCK"),
+    (libc::RLIMIT_AS as u64,         "RLIMIT_AS"),
+    (libc::RLIMIT_LOCKS as u64,      "RLIMIT_LOCKS"),
+    (libc::RLIMIT_SIGPENDING as u64, "RLIMIT_SIGPENDING"),
+    (libc::RLIMIT_MSGQUEUE as u64,   "RLIMIT_MSGQUEUE"),
+    (libc::RLIMIT_NICE as u64,       "RLIMIT_NICE"),
+    (libc::RLIMIT_RTPRIO as u64,     "RLIMIT_RTPRIO"),
+    (libc::RLIMIT_RTTIME as u64,     "RLIMIT_RTTIME"),
+];
+
+
+pub static SOCKET_DOMAINS: &[(u64, &str)] = &[
+    (libc::AF_UNSPEC as u64,    "AF_UNSPEC"),
+    (libc::AF_UNIX as u64,      "AF_UNIX"),
+    (libc::AF_INET as u64,      "AF_INET"),
+    (libc::AF_INET6 as u64,     "AF_INET6"),
+    (libc::AF_NETLINK as u64,   "AF_NETLINK"),
+    (libc::AF_PACKET as u64,    "AF_PACKET"),
+    (libc::AF_BLUETOOTH as u64, "AF_BLUETOOTH"),
+    (libc::AF_ALG as u64,       "AF_ALG"),
+    (libc::AF_VSOCK as u64,     "AF_VSOCK"),
+];
+
+
+static SOCKET_TYPES: Flags = Flags {
+    base: &[
+        (libc::SOCK_STREAM as u64,    "SOCK_STREAM"),
+        (libc::SOCK_DGRAM as u64,     "SOCK_DGRAM"),
+        (libc::SOCK_RAW as u64,       "SOCK_RAW"),
+        (libc::SOCK_RDM as u64,       "SOCK_RDM"),
+        (libc::SOCK_SEQPACKET as u64, "SOCK_SEQPACKET"),
+        (10,                          "SOCK_PACKET"),
+    ],
+    mask: 0xf,
+    bits: &[
+        (libc::SOCK_NONBLOCK as u64, "SOCK_NONBLOCK"),
+        (libc::SOCK_CLOEXEC as u64,  "SOCK_CLOEXEC"),
+    ],
+};
+
+
+/// flags of `accept4`, which take no socket type
+static SOCKET_FLAGS: Flags = Flags {
+    base: &[],
+    mask: 0,
+    bits: &[
+        (libc::SOCK_NONBLOCK as u64, "SOCK_NONBLOCK"),
+        (libc::SOCK_CLOEXEC as u64,  "SOCK_CLOEXEC"),
+    ],
+};
+
+
+static MSG_FLAGS: Flags = Flags {
+    base: &[],
+    mask: 0,
+    bits: &[
+        (libc::MSG_OOB as u64,          "MSG_OOB"),
+        (libc::MSG_PEEK as u64,         "MSG_PEEK"),
+        (libc::MSG_DONTROUTE as u64,    "MSG_DONTROUTE"),
+        (libc::MSG_CTRUNC as u64,       "MSG_CTRUNC"),
+        (libc::MSG_TRUNC as u64,        "MSG_TRUNC"),
+        (libc::MSG_DONTWAIT as u64,     "MSG_DONTWAIT"),
+        (libc::MSG_EOR as u64,          "MSG_EOR"),
+        (libc::MSG_WAITALL as u64,      "MSG_WAITALL"),
+        (libc::MSG_CONFIRM as u64,      "MSG_CONFIRM"),
+        (libc::MSG_ERRQUEUE as u64,     "MSG_ERRQUEUE"),
+        (libc::MSG_NOSIGNAL as u64,     "MSG_NOSIGNAL"),
+        (libc::MSG_MORE as u64,         "MSG_MORE"),
+        (libc::MSG_WAITFORONE as u64,   "MSG_WAITFORONE"),
+        (libc::MSG_FASTOPEN as u64,     "MSG_FASTOPEN"),
+        (libc::MSG_CMSG_CLOEXEC as u64, "MSG_CMSG_CLOEXEC"),
+    ],
+};
+
+
+static SHUTDOWN_HOW: &[(u64, &str)] = &[
+    (libc::SHUT_RD as u64,   "SHUT_RD"),
+    (libc::SHUT_WR as u64,   "SHUT_WR"),
+    (libc::SHUT_RDWR as u64, "SHUT_RDWR"),
+];
+
+
+static EPOLL_CTL_OPS: &[(u64, &str)] = &[
+    (libc::EPOLL_CTL_ADD as u64, "EPOLL_CTL_ADD"),
+    (libc::EPOLL_CTL_DEL as u64, "EPOLL_CTL_DEL"),
+    (libc::EPOLL_CTL_MOD as u64, "EPOLL_CTL_MOD"),
+];
+
+
+static EPOLL_CREATE_FLAGS: Flags = Flags {
+    base: &[],
+    mask: 0,
+    bits: &[(libc::EPOLL_CLOEXEC as u64, "EPOLL_CLOEXEC")],
+};
+
+
+static EVENTFD_FLAGS: Flags = Flags {
+    base: &[],
+    mask: 0,
+    bits: &[
+        (libc::EFD_SEMAPHORE as u64, "EFD_SEMAPHORE"),
+        (libc::EFD_NONBLOCK as u64,  "EFD_NONBLOCK"),
+        (libc::EFD_CLOEXEC as u64,   "EFD_CLOEXEC"),
+    ],
+};
+
+
+static CLOCK_IDS: &[(u64, &str)] = &[
+    (libc::CLOCK_REALTIME as u64,           "CLOCK_REALTIME"),
+    (libc::CLOCK_MONOTONIC as u64,          "CLOCK_MONOTONIC"),
+    (libc::CLOCK_PROCESS_CPUTIME_ID as u64, "CLOCK_PROCESS_CPUTIME_ID"),
+    (libc::CLOCK_THREAD_CPUTIME_ID as u64,  "CLOCK_THREAD_CPUTIME_ID"),
+    (libc::CLOCK_MONOTONIC_RAW as u64,      "CLOCK_MONOTONIC_RAW"),
+    (libc::CLOCK_REALTIME_COARSE as u64,    "CLOCK_REALTIME_COARSE"),
+    (libc::CLOCK_MONOTONIC_COARSE as u64,   "CLOCK_MONOTONIC_COARSE"),
+    (libc::CLOCK_BOOTTIME as u64,           "CLOCK_BOOTTIME"),
+    (libc::CLOCK_REALTIME_ALARM as u64,     "CLOCK_REALTIME_ALARM"),
+    (libc::CLOCK_BOOTTIME_ALARM as u64,     "CLOCK_BOOTTIME_ALARM"),
+    (libc::CLOCK_TAI as u64,                "CLOCK_TAI"),
+];
+
+
+static TIMER_FLAGS: Flags = Flags {
+    base: &[],
+    mask: 0,
+    bits: &[(libc::TIMER_ABSTIME as u64, "TIMER_ABSTIME")],
+};
+
+
+static GETRANDOM_FLAGS: Flags = Flags {
+    base: &[],
+    mask: 0,
+    bits: &[
+        (libc::GRND_NONBLOCK as u64, "GRND_NONBLOCK"),
+        (libc::GRND_RANDOM as u64,   "GRND_RANDOM"),
+        (4,                          "GRND_INSECURE"),
+    ],
+};
+
+
+static MEMFD_FLAGS: Flags = Flags {
+    base: &[],
+    mask: 0,
+    bits: &[
+        (libc::MFD_CLOEXEC as u64,       "MFD_CLOEXEC"),
+        (libc::MFD_ALLOW_SEALING as u64, "MFD_ALLOW_SEALING"),
+        (libc::MFD_HUGETLB as u64,       "MFD_HUGETLB"),
+        (0x8,                            "MFD_NOEXEC_SEAL"),
+        (0x10,                           "MFD_EXEC"),
+    ],
+};
+
+
+static CLOSE_RANGE_FLAGS: Flags = Flags {
+    base: &[],
+    mask: 0,
+    bits: &[
+        (2, "CLOSE_RANGE_UNSHARE"),
+        (4, "CLOSE_RANGE_CLOEXEC"),
+    ],
+};
+
+
+static SIGNATURES: &[(&str, &[ArgType])] = &[
+    ("accept",            &[Fd, Struct(Sockaddr, Out, One), Ptr]),
+    ("accept4",           &[Fd, Struct(Sockaddr, Out, One), Ptr, Flags(&SOCKET_FLAGS)]),
+    ("access",            &[Str, Flags(&ACCESS_MODES)]),
+    ("arch_prctl",        &[Enum(ARCH_PRCTL_CODES), Ptr]),
+    ("bind",              &[Fd, Struct(Sockaddr, In, One), Size]),
+    ("brk",               &[Ptr]),
+    ("chdir",             &[Str]),
+    ("chmod",             &[Str, Mode]),
+    ("clock_gettime",     &[Enum(CLOCK_IDS), Struct(Timespec, Out, One)]),
+    ("clock_nanosleep",   &[Enum(CLOCK_IDS), Flags(&TIMER_FLAGS), Struct(Timespec, In, One), Struct(Timespec, Out, One)]),
+    ("clone",             &[Flags(&CLONE_FLAGS), Ptr, Ptr, Ptr, Ptr]),
+    ("clone3",            &[Ptr, Size]),
+    ("close",             &[Fd]),
+    ("close_range",       &[Fd, Fd, Flags(&CLOSE_RANGE_FLAGS)]),
+    ("connect",           &[Fd, Struct(Sockaddr, In, One), Size]),
+    ("creat",             &[Str, Mode]),
+    ("dup",               &[Fd]),
+    ("dup2",              &[Fd, Fd]),
+    ("dup3",              &[Fd, Fd, Flags(&FD_FLAGS)]),
+    ("epoll_create1",     &[Flags(&EPOLL_CREATE_FLAGS)]),
+    ("epoll_ctl",         &[Fd, Enum(EPOLL_CTL_OPS), Fd, Struct(EpollEvent, In, One)]),
+    ("epoll_pwait",       &[Fd, Struct(EpollEvent, Out, Ret), Int, Int, Ptr, Size]),
+    ("epoll_wait",        &[Fd, Struct(EpollEvent, Out, Ret), Int, Int]),
+    ("eventfd2",          &[Int, Flags(&EVENTFD_FLAGS)]),
+    ("execve",            &[Str, Ptr, Ptr]),
+    ("execveat",          &[DirFd, Str, Ptr, Ptr, Flags(&AT_FLAGS)]),
+    ("exit",              &[Int]),
+    ("exit_group",        &[Int]),
+    ("faccessat",         &[DirFd, Str, Flags(&ACCESS_MODES)]),
+    ("faccessat2",        &[DirFd, Str, Flags(&ACCESS_MODES), Flags(&FACCESSAT_FLAGS)]),
+    ("fadvise64",         &[Fd, Loff, Size, Int]),
+    ("fadvise64_64",      &[Fd, Loff, Loff, Int]),
+    ("fallocate",         &[Fd, Int, Loff, Loff]),
+    ("fchdir",            &[Fd]),
+    ("fchmod",            &[Fd, Mode]),
+    ("fchmodat",          &[DirFd, Str, Mode]),
+    ("fchown",            &[Fd, Int, Int]),
+    ("fchownat",          &[DirFd, Str, Int, Int, Flags(&AT_FLAGS)]),
+    ("fcntl",             &[Fd, Enum(FCNTL_CMDS), Num]),
+    ("fcntl64",           &[Fd, Enum(FCNTL_CMDS), Num]),
+    ("fdatasync",         &[Fd]),
+    ("fstat",             &[Fd, Struct(Stat, Out, One)]),
+    ("fstat64",           &[Fd, Struct(Stat64, Out, One)]),
+    ("fstatat64",         &[DirFd, Str, Struct(Stat64, Out, One), Flags(&AT_FLAGS)]),
+    ("fsync",             &[Fd]),
+    ("ftruncate",         &[Fd, Long]),
+    ("ftruncate64",       &[Fd, Loff]),
+    ("futex",             &[Ptr, Flags(&FUTEX_OPS), Int, Ptr, Ptr, Int]),
+    ("getcwd",            &[Ptr, Size]),
+    ("getdents",          &[Fd, Ptr, Size]),
+    ("getdents64",        &[Fd, Ptr, Size]),
+    ("getpeername",       &[Fd, Struct(Sockaddr, Out, One), Ptr]),
+    ("getrandom",         &[Ptr, Size, Flags(&GETRANDOM_FLAGS)]),
+    ("getrlimit",         &[Enum(RLIMIT_RESOURCES), Struct(Rlimit, Out, One)]),
+    ("getsockname",       &[Fd, Struct(Sockaddr, Out, One), Ptr]),
+    ("getsockopt",        &[Fd, Int, Int, Ptr, Ptr]),
+    ("kill",              &[Int, Signal]),
+    ("linkat",            &[DirFd, Str, DirFd, Str, Flags(&AT_FLAGS)]),
+    ("listen",            &[Fd, Int]),
+    ("lseek",             &[Fd, Long, Enum(SEEK_WHENCE)]),
+    ("lstat",             &[Str, Struct(Stat, Out, One)]),
+    ("lstat64",           &[Str, Struct(Stat64, Out, One)]),
+    ("madvise",           &[Ptr, Size, Enum(MADVISE_ADVICE)]),
+    ("memfd_create",      &[Str, Flags(&MEMFD_FLAGS)]),
+    ("mkdir",             &[Str, Mode]),
+    ("mkdirat",           &[DirFd, Str, Mode]),
+    ("mknod",             &[Str, Mode, Num]),
+    ("mknodat",           &[DirFd, Str, Mode, Num]),
+    ("mmap",              &[Ptr, Size, Flags(&MMAP_PROT), Flags(&MMAP_FLAGS), Fd, Long]),
+    ("mmap2",             &[Ptr, Size, Flags(&MMAP_PROT), Flags(&MMAP_FLAGS), Fd, Long]),
+    ("mprotect",          &[Ptr, Size, Flags(&MMAP_PROT)]),
+    ("mremap",            &[Ptr, Size, Size, Flags(&MREMAP_FLAGS), Ptr]),
+    ("munmap",            &[Ptr, Size]),
+    ("nanosleep",         &[Struct(Timespec, In, One), Struct(Timespec, Out, One)]),
+    ("newfstatat",        &[DirFd, Str, Struct(Stat, Out, One), Flags(&AT_FLAGS)]),
+    ("old_mmap",          &[Struct(MmapArgStruct, In, One)]),
+    ("open",              &[Str, Flags(&OPEN_FLAGS), Mode]),
+    ("openat",            &[DirFd, Str, Flags(&OPEN_FLAGS), Mode]),
+    ("pipe",              &[Ptr]),
+    ("pipe2",             &[Ptr, Flags(&FD_FLAGS)]),
+    ("poll",              &[Struct(Pollfd, InOut, Arg(1)), Size, Int]),
+    ("ppoll",             &[Struct(Pollfd, InOut, Arg(1)), Size, Struct(Timespec, In, One), Ptr, Size]),
+    ("pread64",           &[Fd, Buf(Out, Ret), Size, Loff]),
+    ("prlimit64",         &[Int, Enum(RLIMIT_RESOURCES), Struct(Rlimit64, In, One), Struct(Rlimit64, Out, One)]),
+    ("pwrite64",          &[Fd, Buf(In, Arg(2)), Size, Loff]),
+    ("read",              &[Fd, Buf(Out, Ret), Size]),
+    ("readahead",         &[Fd, Loff, Size]),
+    ("readlink",          &[Str, Ptr, Size]),
+    ("readlinkat",        &[DirFd, Str, Ptr, Size]),
+    ("readv",             &[Fd, Struct(Iovec, In, Arg(2)), Int]),
+    ("recvfrom",          &[Fd, Buf(Out, Ret), Size, Flags(&MSG_FLAGS), Struct(Sockaddr, Out, One), Ptr]),
+    ("recvmsg",           &[Fd, Ptr, Flags(&MSG_FLAGS)]),
+    ("rename",            &[Str, Str]),
+    ("renameat",          &[DirFd, Str, DirFd, Str]),
+    ("renameat2",         &[DirFd, Str, DirFd, Str, Flags(&RENAME_FLAGS)]),
+    ("rmdir",             &[Str]),
+    ("rt_sigaction",      &[Signal, Ptr, Ptr, Size]),
+    ("rt_sigprocmask",    &[Enum(SIGPROCMASK_HOW), Ptr, Ptr, Size]),
+    ("sendfile",          &[Fd, Fd, Ptr, Size]),
+    ("sendmsg",           &[Fd, Ptr, Flags(&MSG_FLAGS)]),
+    ("sendto",            &[Fd, Buf(In, Arg(2)), Size, Flags(&MSG_FLAGS), Struct(Sockaddr, In, One), Size]),
+    ("set_robust_list",   &[Ptr, Size]),
+    ("set_tid_address",   &[Ptr]),
+    ("setrlimit",         &[Enum(RLIMIT_RESOURCES), Struct(Rlimit, In, One)]),
+    ("setsockopt",        &[Fd, Int, Int, Ptr, Size]),
+    ("shutdown",          &[Fd, Enum(SHUTDOWN_HOW)]),
+    ("socket",            &[Enum(SOCKET_DOMAINS), Flags(&SOCKET_TYPES), Int]),
+    ("socketpair",        &[Enum(SOCKET_DOMAINS), Flags(&SOCKET_TYPES), Int, Ptr]),
+    ("stat",              &[Str, Struct(Stat, Out, One)]),
+    ("stat64",            &[Str, Struct(Stat64, Out, One)]),
+    ("statx",             &[DirFd, Str, Flags(&AT_FLAGS), Num, Ptr]),
+    ("symlinkat",         &[Str, DirFd, Str]),
+    ("sync_file_range",   &[Fd, Loff, Loff, Int]),
+    ("tgkill",            &[Int, Int, Signal]),
+    ("tkill",             &[Int, Signal]),
+    ("truncate",          &[Str, Long]),
+    ("truncate64",        &[Str, Loff]),
+    ("umask",             &[Mode]),
+    ("unlink",            &[Str]),
+    ("unlinkat",          &[DirFd, Str, Flags(&UNLINKAT_FLAGS)]),
+    ("utimensat",         &[DirFd, Str, Ptr, Flags(&AT_FLAGS)]),
+    ("wait4",             &[Int, Ptr, Flags(&WAIT_OPTIONS), Ptr]),
+    ("write",             &[Fd, Buf(In, Arg(2)), Size]),
+    ("writev",            &[Fd, Struct(Iovec, In, Arg(2)), Int]),
+];
@@ -0,0 +1,393 @@
+//! event.rs
+//!
+//!     Defines the events observed while tracing a process.
+//!
+//!     Besides syscalls, tracees receive signals which are
+//!     reported to the tracer before delivery, and these are
+//!     decoded from their siginfo in the style of strace. The
+//!     termination of each tracee is recorded as a final event.
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use libc::c_int;
+use nix::sys::signal::Signal;
+use serde::Serialize;
+
+use syscall::Syscall;
+use summary::Summary;
+use arch::Arch;
+
+// first realtime signal as numbered by the kernel, rather than by libc
+static KERNEL_SIGRTMIN: c_int = 32;
+
+
+/// Defines any event that can be recorded in a trace.
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum Event {
+    Syscall(Syscall),
+    Signal(SignalEvent),
+    Exit(ExitEvent),
+    Personality(PersonalityEvent),
+    Summary(Summary),
+}
+
+
+impl Event {
+
+    /// `pid()` returns the process (thread group) that the event occurred in, if any.
+    pub fn pid(&self) -> Option<i32> {
+        match self {
+            Event::Syscall(syscall) => Some(syscall.pid()),
+            Event::Signal(signal) => Some(signal.pid),
+            Event::Exit(exit) => Some(exit.pid),
+            Event::Personality(personality) => Some(personality.pid),
+            Event::Summary(_) => None,
+        }
+    }
+
+    /// `timestamp_ns()` returns the wall-clock time at which the event occurred, if any.
+    pub fn timestamp_ns(&self) -> Option<u64> {
+        match self {
+            Event::Syscall(syscall) => Some(syscall.timestamp_ns()),
+            Event::Signal(signal) => Some(signal.timestamp_ns),
+            Event::Exit(exit) => Some(exit.timestamp_ns),
+            Event::Personality(personality) => Some(personality.timestamp_ns),
+            Event::Summary(_) => None,
+        }
+    }
+
+    /// `tid()` returns the thread that the event occurred in, if any.
+    pub fn tid(&self) -> Option<i32> {
+        match self {
+            Event::Syscall(syscall) => Some(syscall.tid()),
+            Event::Signal(signal) => Some(signal.tid),
+            Event::Exit(exit) => Some(exit.tid),
+            Event::Personality(personality) => Some(personality.tid),
+            Event::Summary(_) => None,
+        }
+    }
+}
+
+
+impl fmt::Display for Event {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::Syscall(syscall) => write!(f, "{}", syscall),
+            Event::Signal(signal) => write!(f, "{}", signal),
+            Event::Exit(exit) => write!(f, "{}", exit),
+            Event::Personality(personality) => write!(f, "{}", personality),
+            Event::Summary(summary) => write!(f, "{}", summary),
+        }
+    }
+}
+
+
+/// Decoded contents of the siginfo of a delivered signal. Which fields are
+/// set depends on both the signal and the code describing how it was sent.
+#[derive(Serialize)]
+pub struct SigInfo {
+    si_signo: String,
+    si_code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    si_pid: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    si_uid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    si_status: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    si_utime: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    si_stime: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    si_addr: Option<u64>,
+}
+
+
+impl SigInfo {
+
+    /// `new()` decodes a raw siginfo retrieved from the tracee with PTRACE_GETSIGINFO.
+    /// Note that the width of `clock_t` differs between architectures.
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new(raw: &libc::siginfo_t) -> Self {
+        let mut info = Self {
+            si_signo: signal_name(raw.si_signo),
+            si_code: si_code_name(raw.si_signo, raw.si_code),
+            si_pid: None,
+            si_uid: None,
+            si_status: None,
+            si_utime: None,
+            si_stime: None,
+            si_addr: None,
+        };
+
+        // siginfo is a union, so only read the members that are valid for this signal
+        unsafe {
+            if raw.si_code <= 0 {
+                info.si_pid = Some(raw.si_pid());
+                info.si_uid = Some(raw.si_uid());
+            } else if raw.si_signo == libc::SIGCHLD {
+                info.si_pid = Some(raw.si_pid());
+                info.si_uid = Some(raw.si_uid());
+                info.si_status = Some(raw.si_status());
+                info.si_utime = Some(raw.si_utime() as i64);
+                info.si_stime = Some(raw.si_stime() as i64);
+            } else if is_fault(raw.si_signo) {
+                info.si_addr = Some(raw.si_addr() as u64);
+            }
+        }
+        info
+    }
+}
+
+
+impl fmt::Display for SigInfo {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{{si_signo={}, si_code={}", self.si_signo, self.si_code)?;
+        if let Some(pid) = self.si_pid {
+            write!(f, ", si_pid={}", pid)?;
+        }
+        if let Some(uid) = self.si_uid {
+            write!(f, ", si_uid={}", uid)?;
+        }
+        if let Some(status) = self.si_status {
+
+            // the status is an exit code for exited children, and a signal otherwise
+            if self.si_code == "CLD_EXITED" {
+                write!(f, ", si_status={}", status)?;
+            } else {
+                write!(f, ", si_status={}", signal_name(status))?;
+            }
+        }
+        if let (Some(utime), Some(stime)) = (self.si_utime, self.si_stime) {
+            write!(f, ", si_utime={}, si_stime={}", utime, stime)?;
+        }
+        match self.si_addr {
+            Some(0) => write!(f, ", si_addr=NULL")?,
+            Some(addr) => write!(f, ", si_addr={:#x}", addr)?,
+            None => {}
+        }
+        write!(f, "}}")
+    }
+}
+
+
+/// Defines a signal about to be delivered to a tracee, or a group-stop
+/// caused by a stopping signal.
+#[derive(Serialize)]
+pub struct SignalEvent {
+    pid: i32,
+    tid: i32,
+    timestamp_ns: u64,
+    signal: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    siginfo: Option<SigInfo>,
+    stopped: bool,
+}
+
+
+impl SignalEvent {
+
+    /// `delivered()` creates an event for a signal that is being delivered to the tracee.
+    pub fn delivered(pid: i32, tid: i32, raw: &libc::siginfo_t) -> Self {
+        Self {
+            pid,
+            tid,
+            timestamp_ns: timestamp_ns(),
+            signal: signal_name(raw.si_signo),
+            siginfo: Some(SigInfo::new(raw)),
+            stopped: false,
+        }
+    }
+
+    /// `stopped()` creates an event for a tracee that entered a group-stop.
+    pub fn stopped(pid: i32, tid: i32, signo: c_int) -> Self {
+        Self {
+            pid,
+            tid,
+            timestamp_ns: timestamp_ns(),
+            signal: signal_name(signo),
+            siginfo: None,
+            stopped: true,
+        }
+    }
+}
+
+
+impl fmt::Display for SignalEvent {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.stopped {
+            return write!(f, "--- stopped by {} ---", self.signal);
+        }
+        match self.siginfo {
+            Some(ref info) => write!(f, "--- {} {} ---", self.signal, info),
+            None => write!(f, "--- {} ---", self.signal),
+        }
+    }
+}
+
+
+/// Defines the termination of a tracee, either by exiting normally with an
+/// exit code, or by being killed by a signal.
+#[derive(Serialize)]
+pub struct ExitEvent {
+    pid: i32,
+    tid: i32,
+    timestamp_ns: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signal: Option<String>,
+    core_dumped: bool,
+}
+
+
+impl ExitEvent {
+
+    /// `new()` decodes a wait status for a tracee that has terminated.
+    pub fn new(pid: i32, tid: i32, status: c_int) -> Self {
+        let (code, signal, core_dumped) = if libc::WIFEXITED(status) {
+            (Some(libc::WEXITSTATUS(status)), None, false)
+        } else {
+            (None, Some(signal_name(libc::WTERMSIG(status))), libc::WCOREDUMP(status))
+        };
+        Self { pid, tid, timestamp_ns: timestamp_ns(), code, signal, core_dumped }
+    }
+}
+
+
+impl fmt::Display for ExitEvent {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.code, &self.signal) {
+            (Some(code), _) => write!(f, "+++ exited with {} +++", code),
+            (None, Some(signal)) if self.core_dumped => write!(f, "+++ killed by {} (core dumped) +++", signal),
+            (None, Some(signal)) => write!(f, "+++ killed by {} +++", signal),
+            (None, None) => write!(f, "+++ exited +++"),
+        }
+    }
+}
+
+
+/// Defines a change in the architecture a tracee makes syscalls through, such as
+/// after it executed a 32-bit binary.
+#[derive(Serialize)]
+pub struct PersonalityEvent {
+    pid: i32,
+    tid: i32,
+    timestamp_ns: u64,
+    arch: Arch,
+}
+
+
+impl PersonalityEvent {
+
+    pub fn new(pid: i32, tid: i32, arch: Arch) -> Self {
+        Self { pid, tid, timestamp_ns: timestamp_ns(), arch }
+    }
+}
+
+
+impl fmt::Display for PersonalityEvent {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mode = match self.arch {
+            Arch::X86_64 => "64 bit",
+            Arch::I386 => "32 bit",
+            arch => arch.name(),
+        };
+        write!(f, "[ Process PID={} runs in {} mode. ]", self.tid, mode)
+    }
+}
+
+
+/// `timestamp_ns()` returns the current wall-clock time in nanoseconds since the epoch.
+pub fn timestamp_ns() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+}
+
+
+/// `signal_name()` returns the name of a signal number, with realtime signals
+/// numbered relative to the kernel's SIGRTMIN.
+pub fn signal_name(signo: c_int) -> String {
+    match Signal::from_c_int(signo) {
+        Ok(signal) => format!("{:?}", signal),
+        Err(_) if signo >= KERNEL_SIGRTMIN => format!("SIGRT_{}", signo - KERNEL_SIGRTMIN),
+        Err(_) => format!("{}", signo),
+    }
+}
+
+
+/// `is_fault()` checks if a signal is raised by a hardware fault, in which case
+/// its siginfo carries the faulting address.
+fn is_fault(signo: c_int) -> bool {
+    signo == libc::SIGSEGV || signo == libc::SIGBUS || signo == libc::SIGILL
+        || signo == libc::SIGFPE || signo == libc::SIGTRAP
+}
+
+
+/// `si_code_name()` decodes the si_code of a siginfo, whose meaning depends
+/// on the signal it was sent with when positive.
+fn si_code_name(signo: c_int, code: c_int) -> String {
+    let name = match code {
+        0 => Some("SI_USER"),
+        0x80 => Some("SI_KERNEL"),
+        -1 => Some("SI_QUEUE"),
+        -2 => Some("SI_TIMER"),
+        -3 => Some("SI_MESGQ"),
+        -4 => Some("SI_ASYNCIO"),
+        -5 => Some("SI_SIGIO"),
+        -6 => Some("SI_TKILL"),
+        _ => match (signo, code) {
+            (libc::SIGCHLD, 1) => Some("CLD_EXITED"),
+            (libc::SIGCHLD, 2) => Some("CLD_KILLED"),
+            (libc::SIGCHLD, 3) => Some("CLD_DUMPED"),
+            (libc::SIGCHLD, 4) => Some("CLD_TRAPPED"),
+            (libc::SIGCHLD, 5) => Some("CLD_STOPPED"),
+            (libc::SIGCHLD, 6) => Some("CLD_CONTINUED"),
+            (libc::SIGSEGV, 1) => Some("SEGV_MAPERR"),
+            (libc::SIGSEGV, 2) => Some("SEGV_ACCERR"),
+            (libc::SIGSEGV, 3) => Some("SEGV_BNDERR"),
+            (libc::SIGSEGV, 4) => Some("SEGV_PKUERR"),
+            (libc::SIGBUS, 1) => Some("BUS_ADRALN"),
+            (libc::SIGBUS, 2) => Some("BUS_ADRERR"),
+            (libc::SIGBUS, 3) => Some("BUS_OBJERR"),
+            (libc::SIGILL, 1) => Some("ILL_ILLOPC"),
+            (libc::SIGILL, 2) => Some("ILL_ILLOPN"),
+            (libc::SIGILL, 3) => Some("ILL_ILLADR"),
+            (libc::SIGILL, 4) => Some("ILL_ILLTRP"),
+            (libc::SIGILL, 5) => Some("ILL_PRVOPC"),
+            (libc::SIGILL, 6) => Some("ILL_PRVREG"),
+            (libc::SIGILL, 7) => Some("ILL_COPROC"),
+            (libc::SIGILL, 8) => Some("ILL_BADSTK"),
+            (libc::SIGFPE, 1) => Some("FPE_INTDIV"),
+            (libc::SIGFPE, 2) => Some("FPE_INTOVF"),
+            (libc::SIGFPE, 3) => Some("FPE_FLTDIV"),
+            (libc::SIGFPE, 4) => Some("FPE_FLTOVF"),
+            (libc::SIGFPE, 5) => Some("FPE_FLTUND"),
+            (libc::SIGFPE, 6) => Some("FPE_FLTRES"),
+            (libc::SIGFPE, 7) => Some("FPE_FLTINV"),
+            (libc::SIGFPE, 8) => Some("FPE_FLTSUB"),
+            (libc::SIGTRAP, 1) => Some("TRAP_BRKPT"),
+            (libc::SIGTRAP, 2) => Some("TRAP_TRACE"),
+            (libc::SIGIO, 1) => Some("POLL_IN"),
+            (libc::SIGIO, 2) => Some("POLL_OUT"),
+            (libc::SIGIO, 3) => Some("POLL_MSG"),
+            (libc::SIGIO, 4) => Some("POLL_ERR"),
+            (libc::SIGIO, 5) => Some("POLL_PRI"),
+            (libc::SIGIO, 6) => Some("POLL_HUP"),
+            (libc::SIGSYS, 1) => Some("SYS_SECCOMP"),
+            _ => None,
+        }
+    };
+    match name {
+        Some(name) => name.to_string(),
+        None => format!("{}", code),
+    }
+}
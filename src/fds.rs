@@ -0,0 +1,421 @@
+//! fds.rs
+//!
+//!     Tracks the file descriptors of each traced process, such
+//!     that descriptor arguments can be annotated with the file,
+//!     socket or pipe they refer to, as with `-y`.
+//!
+//!     Tables are seeded from /proc/PID/fd, and kept up to date
+//!     from the results of the syscalls that create, duplicate
+//!     and close descriptors. Sockets are described through the
+//!     tables in /proc/PID/net of the tracee's network namespace.
+
+use std::io;
+use std::fs;
+use std::mem;
+use std::collections::BTreeMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use libc::pid_t;
+
+use ptrace::helpers;
+use syscall::Syscall;
+
+// `flags` of close_range that marks descriptors close-on-exec rather than closing them
+const CLOSE_RANGE_CLOEXEC: u64 = 4;
+
+
+/// Maps the open descriptors of a process to the resources they refer to, such as
+/// `/etc/passwd`, `pipe:[1234]` or `TCP:[127.0.0.1:443]`.
+#[derive(Default)]
+pub struct FdTable {
+    fds: BTreeMap<i32, String>,
+}
+
+
+impl FdTable {
+
+    /// `from_proc()` seeds a table with every descriptor a process currently has open.
+    pub fn from_proc(pid: pid_t) -> io::Result<Self> {
+        let mut fds = BTreeMap::new();
+        for entry in fs::read_dir(format!("/proc/{}/fd", pid))? {
+            let fd = match entry?.file_name().to_str().and_then(|name| name.parse::<i32>().ok()) {
+                Some(fd) => fd,
+                None => continue,
+            };
+            if let Some(resource) = describe(pid, fd) {
+                fds.insert(fd, resource);
+            }
+        }
+        Ok(Self { fds })
+    }
+
+
+    /// `get()` returns the resource a descriptor refers to, if it is open.
+    pub fn get(&self, fd: i32) -> Option<&str> {
+        self.fds.get(&fd).map(String::as_str)
+    }
+
+
+    /// `update()` applies the effect of a completed syscall on the descriptors of the
+    /// process, which is read back from /proc and tracee memory while it is still stopped
+    /// at SYS_EXIT. Returns the descriptor the syscall returned, if it created one.
+    pub fn update(&mut self, syscall: &Syscall) -> Option<i32> {
+        let (pid, tid) = (syscall.pid(), syscall.tid());
+        let ret = match syscall.ret() {
+            Some(ret) if !syscall.failed() => ret,
+            _ => return None,
+        };
+        let arg = |idx: usize| syscall.args().get(idx).cloned().unwrap_or(0);
+        let fd = ret as i32;
+
+        match syscall.name() {
+
+            // syscalls that return a new descriptor
+            "open" | "openat" | "openat2" | "creat" | "open_by_handle_at" | "socket" | "accept"
+            | "accept4" | "epoll_create" | "epoll_create1" | "eventfd" | "eventfd2" | "timerfd_create"
+            | "signalfd" | "signalfd4" | "memfd_create" | "memfd_secret" | "inotify_init"
+            | "inotify_init1" | "fanotify_init" | "pidfd_open" | "pidfd_getfd" | "perf_event_open"
+            | "userfaultfd" | "io_uring_setup" | "fsopen" | "fsmount" | "fspick" | "open_tree" => {
+                self.describe(tid, fd);
+                Some(fd)
+            },
+
+            // syscalls that duplicate an existing descriptor
+            "dup" | "dup2" | "dup3" => {
+                self.duplicate(tid, arg(0) as i32, fd);
+                Some(fd)
+            },
+            "fcntl" | "fcntl64" if arg(1) as i32 == libc::F_DUPFD || arg(1) as i32 == libc::F_DUPFD_CLOEXEC => {
+                self.duplicate(tid, arg(0) as i32, fd);
+                Some(fd)
+            },
+
+            // syscalls that return a pair of descriptors through memory
+            "pipe" | "pipe2" | "socketpair" => {
+                let addr = if syscall.name() == "socketpair" { arg(3) } else { arg(0) };
+                if let Ok(bytes) = helpers::read_memory(tid, addr, 2 * mem::size_of::<i32>()) {
+                    for pair in bytes.chunks(4) {
+                        self.describe(tid, i32::from_ne_bytes([pair[0], pair[1], pair[2], pair[3]]));
+                    }
+                }
+                None
+            },
+
+            // syscalls that change the addresses a socket is described by
+            "bind" | "connect" | "listen" => {
+                self.describe(tid, arg(0) as i32);
+                None
+            },
+
+            "close" => {
+                self.fds.remove(&(arg(0) as i32));
+                None
+            },
+            "close_range" if arg(2) & CLOSE_RANGE_CLOEXEC == 0 => {
+                let (first, last) = (arg(0) as u32, arg(1) as u32);
+                self.fds.retain(|&fd, _| fd < 0 || (fd as u32) < first || fd as u32 > last);
+                None
+            },
+
+            // descriptors marked close-on-exec are closed by a successful exec
+            "execve" | "execveat" => {
+                if let Ok(table) = FdTable::from_proc(pid) {
+                    *self = table;
+                }
+                None
+            },
+            _ => None,
+        }
+    }
+
+
+    /// `describe()` records the resource a descriptor refers to, as currently seen in /proc.
+    fn describe(&mut self, pid: pid_t, fd: i32) {
+        match describe(pid, fd) {
+            Some(resource) => self.fds.insert(fd, resource),
+            None => self.fds.remove(&fd),
+        };
+    }
+
+
+    /// `duplicate()` records a descriptor as referring to the same resource as another.
+    fn duplicate(&mut self, pid: pid_t, old: i32, new: i32) {
+        match self.fds.get(&old).cloned() {
+            Some(resource) => {
+                self.fds.insert(new, resource);
+            },
+            None => self.describe(pid, new),
+        }
+    }
+}
+
+
+/// `describe()` resolves what a descriptor of a process refers to through its link in
+/// /proc/PID/fd, which is either a path, or a type and inode such as `pipe:[1234]`.
+/// Sockets are resolved further into their protocol and addresses if possible.
+pub fn describe(pid: pid_t, fd: i32) -> Option<String> {
+    let link = fs::read_link(format!("/proc/{}/fd/{}", pid, fd)).ok()?;
+    let link = link.to_string_lossy().into_owned();
+    let socket = link.strip_prefix("socket:[")
+                     .and_then(|inode| inode.strip_suffix(']'))
+                     .and_then(|inode| inode.parse::<u64>().ok())
+                     .and_then(|inode| describe_socket(pid, inode));
+    Some(socket.unwrap_or(link))
+}
+
+
+/// `describe_socket()` finds a socket by inode in the tables of /proc/PID/net, and renders
+/// it as strace does, e.g. `TCP:[127.0.0.1:443]` for a listening socket, `TCP:[10.0.0.2:5678->
+/// 93.184.216.34:80]` for a connected one, or `UNIX-STREAM:[1234,"/run/app.sock"]`.
+fn describe_socket(pid: pid_t, inode: u64) -> Option<String> {
+    let inode = inode.to_string();
+    for &(table, proto) in &[("tcp", "TCP"), ("tcp6", "TCPv6"), ("udp", "UDP"), ("udp6", "UDPv6")] {
+        let contents = match fs::read_to_string(format!("/proc/{}/net/{}", pid, table)) {
+            Ok(contents) => contents,
+            Err(_) => continue,
+        };
+
+        // sl local_address rem_address st tx_queue:rx_queue tr:tm->when retrnsmt uid timeout inode
+        for line in contents.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.get(9) != Some(&inode.as_str()) {
+                continue;
+            }
+            let local = inet_address(fields[1])?;
+            return Some(match inet_address(fields[2]) {
+                Some(ref remote) if !remote.ends_with(":0") => format!("{}:[{}->{}]", proto, local, remote),
+                _ => format!("{}:[{}]", proto, local),
+            });
+        }
+    }
+
+    // Num RefCount Protocol Flags Type St Inode Path
+    let contents = fs::read_to_string(format!("/proc/{}/net/unix", pid)).ok()?;
+    for line in contents.lines().skip(1) {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.get(6) != Some(&inode.as_str()) {
+            continue;
+        }
+        let proto = match fields[4] {
+            "0001" => "UNIX-STREAM",
+            "0002" => "UNIX-DGRAM",
+            "0005" => "UNIX-SEQPACKET",
+            _ => "UNIX",
+        };
+        return Some(match fields.get(7) {
+            Some(path) => format!("{}:[{},\"{}\"]", proto, inode, path),
+            None => format!("{}:[{}]", proto, inode),
+        });
+    }
+    None
+}
+
+
+/// `inet_address()` parses an address and port as listed in /proc/net/tcp and the like,
+/// where the address is in hex as stored in memory, in 32-bit words of host byte order,
+/// and the port is a plain hex number. IPv6 addresses are enclosed in brackets.
+fn inet_address(field: &str) -> Option<String> {
+    let (addr, port) = field.split_at(field.find(':')?);
+    let port = u16::from_str_radix(&port[1..], 16).ok()?;
+    let mut bytes = Vec::with_capacity(16);
+    for idx in (0..addr.len()).step_by(8) {
+        let word = u32::from_str_radix(addr.get(idx..idx + 8)?, 16).ok()?;
+        bytes.extend_from_slice(&word.to_ne_bytes());
+    }
+    match bytes.len() {
+        4 => Some(format!("{}:{}", Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]), port)),
+        16 => {
+            let mut octets = [0; 16];
+            octets.copy_from_slice(&bytes);
+            Some(format!("[{}]:{}", Ipv6Addr::from(octets), port))
+        },
+        _ => None,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process, ptr};
+    use std::fs::File;
+    use std::net::{TcpListener, TcpStream};
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::net::UnixListener;
+    use libc::c_int;
+
+    use arch::Arch;
+    use filter::Filter;
+    use sink::JsonLinesSink;
+    use syscall::SyscallManager;
+    use super::*;
+
+
+    /// `manager()` builds a manager that tracks descriptors and discards its output.
+    fn manager(filter: Filter) -> SyscallManager {
+        SyscallManager::new(Box::new(JsonLinesSink::new(Box::new(io::sink()))), filter, None, true)
+    }
+
+
+    /// `number()` finds the number of a syscall for x86_64, whose table has every syscall
+    /// updating descriptors, on any host.
+    fn number(name: &str) -> u64 {
+        (0..512).find(|&num| Arch::X86_64.syscall_name(num) == Some(name)).unwrap()
+    }
+
+
+    /// `completed()` builds a syscall of a process as seen at SYS_EXIT.
+    fn completed(pid: pid_t, name: &str, args: &[u64], ret: i64) -> Syscall {
+        let mut syscall = manager(Filter::default()).new_syscall(pid, pid, Arch::X86_64, number(name), args.to_vec());
+        syscall.set_return(ret);
+        syscall
+    }
+
+
+    /// `table()` builds a table from descriptors and the resources they refer to.
+    fn table(fds: &[(i32, &str)]) -> FdTable {
+        FdTable { fds: fds.iter().map(|&(fd, resource)| (fd, resource.to_string())).collect() }
+    }
+
+
+    /// `stopped_child()` forks a child that creates a pair of descriptors with `create`,
+    /// and stops itself under ptrace, as if at the SYS_EXIT of the syscall that did so.
+    /// Returns the child and the address the descriptors were written to.
+    fn stopped_child(create: fn(*mut c_int) -> c_int) -> (pid_t, u64) {
+        let mut pair = Box::new([-1 as c_int; 2]);
+        unsafe {
+            let child = libc::fork();
+            if child == 0 {
+                if create(pair.as_mut_ptr()) == 0 && helpers::traceme().is_ok() {
+                    libc::raise(libc::SIGSTOP);
+                }
+                libc::_exit(0);
+            }
+            let mut status = 0;
+            assert_eq!(libc::waitpid(child, &mut status, 0), child);
+            assert!(libc::WIFSTOPPED(status));
+            (child, pair.as_ptr() as u64)
+        }
+    }
+
+
+    fn kill(child: pid_t) {
+        unsafe {
+            libc::kill(child, libc::SIGKILL);
+            libc::waitpid(child, ptr::null_mut(), 0);
+        }
+    }
+
+
+    #[test]
+    fn inet_addresses() {
+
+        // addresses are listed as stored in memory, in words of host byte order
+        assert_eq!(inet_address("0100007F:1F90").unwrap(), "127.0.0.1:8080");
+        assert_eq!(inet_address("00000000:0016").unwrap(), "0.0.0.0:22");
+        assert_eq!(inet_address("00000000000000000000000001000000:0050").unwrap(), "[::1]:80");
+        assert_eq!(inet_address("B80D0120000000000000000001000000:01BB").unwrap(), "[2001:db8::1]:443");
+        assert!(inet_address("0100007F").is_none());
+        assert!(inet_address("7F:0050").is_none());
+    }
+
+
+    #[test]
+    fn sockets() {
+        let pid = process::id() as pid_t;
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        let local = stream.local_addr().unwrap().port();
+        assert_eq!(describe(pid, listener.as_raw_fd()).unwrap(), format!("TCP:[127.0.0.1:{}]", port));
+        assert_eq!(describe(pid, stream.as_raw_fd()).unwrap(),
+                   format!("TCP:[127.0.0.1:{}->127.0.0.1:{}]", local, port));
+
+        let path = env::temp_dir().join(format!("jtrace-fds-{}.sock", pid));
+        let _ = fs::remove_file(&path);
+        let unix = UnixListener::bind(&path).unwrap();
+        let described = describe(pid, unix.as_raw_fd()).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(described.starts_with("UNIX-STREAM:["), "{}", described);
+        assert!(described.ends_with(&format!(",\"{}\"]", path.display())), "{}", described);
+    }
+
+
+    #[test]
+    fn duplicated_descriptors() {
+        let pid = process::id() as pid_t;
+        let mut fds = table(&[(3, "/etc/hostname")]);
+        let cloexec = libc::O_CLOEXEC as u64;
+        assert_eq!(fds.update(&completed(pid, "dup", &[3], 4)), Some(4));
+        assert_eq!(fds.update(&completed(pid, "dup2", &[3, 5], 5)), Some(5));
+        assert_eq!(fds.update(&completed(pid, "dup3", &[3, 6, cloexec], 6)), Some(6));
+        assert_eq!(fds.update(&completed(pid, "fcntl", &[3, libc::F_DUPFD as u64, 10], 10)), Some(10));
+        assert_eq!(fds.update(&completed(pid, "fcntl", &[3, libc::F_DUPFD_CLOEXEC as u64, 0], 11)), Some(11));
+        for fd in &[4, 5, 6, 10, 11] {
+            assert_eq!(fds.get(*fd), Some("/etc/hostname"));
+        }
+
+        // other commands and failed syscalls leave the table as it was
+        assert_eq!(fds.update(&completed(pid, "fcntl", &[3, libc::F_SETFD as u64, 1], 0)), None);
+        assert_eq!(fds.update(&completed(pid, "dup", &[3], -i64::from(libc::EMFILE))), None);
+        assert_eq!(fds.get(0), None);
+        assert_eq!(fds.fds.len(), 6);
+    }
+
+
+    #[test]
+    fn closed_descriptors() {
+        let pid = process::id() as pid_t;
+        let mut fds = table(&[(3, "a"), (4, "b"), (5, "c"), (6, "d"), (7, "e"), (8, "f")]);
+        fds.update(&completed(pid, "close", &[3], 0));
+        fds.update(&completed(pid, "close_range", &[4, 6, 0], 0));
+        assert_eq!(fds.fds.keys().cloned().collect::<Vec<_>>(), vec![7, 8]);
+
+        // descriptors are only marked close-on-exec with CLOSE_RANGE_CLOEXEC
+        fds.update(&completed(pid, "close_range", &[0, u64::from(u32::MAX), CLOSE_RANGE_CLOEXEC], 0));
+        assert_eq!(fds.fds.len(), 2);
+        fds.update(&completed(pid, "close_range", &[8, u64::from(u32::MAX), 0], 0));
+        assert_eq!(fds.fds.keys().cloned().collect::<Vec<_>>(), vec![7]);
+    }
+
+
+    #[test]
+    fn descriptor_pairs() {
+        let (piped, addr) = stopped_child(|pair| unsafe { libc::pipe2(pair, libc::O_CLOEXEC) });
+        let mut pipes = FdTable::default();
+        let ret = pipes.update(&completed(piped, "pipe2", &[addr, libc::O_CLOEXEC as u64], 0));
+        kill(piped);
+        assert_eq!(ret, None);
+        assert_eq!(pipes.fds.len(), 2);
+        assert!(pipes.fds.values().all(|pipe| pipe.starts_with("pipe:[")), "{:?}", pipes.fds);
+
+        let (paired, addr) = stopped_child(|pair| unsafe { libc::socketpair(libc::AF_UNIX, libc::SOCK_STREAM, 0, pair) });
+        let mut sockets = FdTable::default();
+        let args = [libc::AF_UNIX as u64, libc::SOCK_STREAM as u64, 0, addr];
+        sockets.update(&completed(paired, "socketpair", &args, 0));
+        kill(paired);
+        assert_eq!(sockets.fds.len(), 2);
+        assert!(sockets.fds.values().all(|socket| socket.starts_with("UNIX-STREAM:[")), "{:?}", sockets.fds);
+    }
+
+
+    #[test]
+    fn paths_select_descriptors() {
+        let pid = process::id() as pid_t;
+        let path = env::temp_dir().join(format!("jtrace-fds-{}.conf", pid));
+        let file = File::create(&path).unwrap();
+
+        // a read is selected by -P through the file its descriptor refers to
+        let read = |pattern: &str| {
+            let mut filter = Filter::default();
+            filter.add_path(pattern).unwrap();
+            let mut manager = manager(filter);
+            manager.seed_fds(pid);
+            let read = manager.new_syscall(pid, pid, Arch::X86_64, number("read"), vec![file.as_raw_fd() as u64, 0, 16]);
+            manager.selected(&read, &[])
+        };
+        let (conf, etc) = (read("/**/jtrace-fds-*.conf"), read("/etc/**"));
+        fs::remove_file(&path).unwrap();
+        assert!(conf);
+        assert!(!etc);
+    }
+}
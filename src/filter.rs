@@ -0,0 +1,436 @@
+//! filter.rs
+//!
+//!     Implements the expressions that select which syscalls
+//!     are traced, as given with `-e trace=...`.
+//!
+//!     An expression is a comma-separated list of syscall names,
+//!     classes such as `%file`, and regular expressions written
+//!     as `/regex`, each of which can be negated with `!`.
+//!
+//!     Sets of file descriptors, as given with `-e read=...` and
+//!     `-e write=...`, are written the same way with descriptor
+//!     numbers as items.
+//!
+//!     Syscalls can further be restricted to those that refer to
+//!     a path, as given with `-P`, or that operate on a set of
+//!     descriptors, as given with `--fd`.
+
+use regex::Regex;
+
+use sysent::{self, Class};
+
+
+/// Matches syscalls by name, class or regular expression.
+enum Matcher {
+    All,
+    Name(String),
+    Class(Class),
+    Regex(Regex),
+}
+
+
+impl Matcher {
+
+    /// `parse()` parses a single item of a filter expression.
+    fn parse(item: &str) -> Result<Self, String> {
+        if item == "all" {
+            return Ok(Matcher::All);
+        }
+        if let Some(re) = item.strip_prefix('/') {
+            return Regex::new(re)
+                .map(Matcher::Regex)
+                .map_err(|e| format!("invalid regex '{}': {}", re, e));
+        }
+        if let Some(class) = item.strip_prefix('%') {
+            let class = match class {
+                "file" => Class::FILE,
+                "process" => Class::PROCESS,
+                "network" | "net" => Class::NETWORK,
+                "signal" => Class::SIGNAL,
+                "ipc" => Class::IPC,
+                "memory" => Class::MEMORY,
+                "desc" => Class::DESC,
+                class => return Err(format!("invalid syscall class '%{}'", class)),
+            };
+            return Ok(Matcher::Class(class));
+        }
+        match sysent::lookup(item) {
+            Some(_) => Ok(Matcher::Name(item.to_string())),
+            None => Err(format!("invalid syscall '{}'", item)),
+        }
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            Matcher::All => true,
+            Matcher::Name(n) => n == name,
+            Matcher::Class(class) => sysent::classes(name).intersects(*class),
+            Matcher::Regex(re) => re.is_match(name),
+        }
+    }
+}
+
+
+/// Selects the syscalls to trace. Items are applied in order, with later items
+/// taking precedence, such that `%file,!openat` traces every file syscall other
+/// than `openat`. Every syscall is traced if no items are given, and if the first
+/// item is negated, every syscall it does not match is traced. Traced syscalls
+/// are only written out if they refer to one of `paths` and operate on one of
+/// `fds`, if either is given.
+#[derive(Default)]
+pub struct Filter {
+    items: Vec<(bool, Matcher)>,
+    paths: Vec<Regex>,
+    fds: Option<FdSet>,
+}
+
+
+impl Filter {
+
+    /// `add()` parses a filter expression and appends its items to the filter.
+    pub fn add(&mut self, expr: &str) -> Result<(), String> {
+        for item in expr.split(',').filter(|item| !item.is_empty()) {
+            let (negated, item) = match item.strip_prefix('!') {
+                _ if item == "none" => (true, "all"),
+                Some(item) => (true, item),
+                None => (false, item),
+            };
+            self.items.push((negated, Matcher::parse(item)?));
+        }
+        Ok(())
+    }
+
+
+    /// `add_path()` selects syscalls that refer to paths matching a glob pattern, in which
+    /// `*` and `?` match within a path component, `**/` matches any number of components,
+    /// and `[...]` matches a set of characters, negated with `[!...]`.
+    pub fn add_path(&mut self, pattern: &str) -> Result<(), String> {
+        let re = Regex::new(&glob_regex(pattern)).map_err(|e| format!("invalid pattern '{}': {}", pattern, e))?;
+        self.paths.push(re);
+        Ok(())
+    }
+
+
+    /// `add_fds()` selects syscalls that operate on the descriptors of a set expression.
+    pub fn add_fds(&mut self, expr: &str) -> Result<(), String> {
+        self.fds.get_or_insert_with(FdSet::default).add(expr)
+    }
+
+
+    /// `filters_paths()` checks if syscalls are selected by path, which requires the
+    /// descriptors of each process to be tracked.
+    pub fn filters_paths(&self) -> bool {
+        !self.paths.is_empty()
+    }
+
+
+    /// `selects()` checks if a traced syscall is to be written out, given the paths it
+    /// refers to by name, and the descriptors it operates on along with the resources
+    /// they refer to, if known.
+    pub fn selects(&self, paths: &[String], fds: &[(i32, Option<&str>)]) -> bool {
+        let by_path = self.paths.is_empty() || paths
+            .iter()
+            .map(String::as_str)
+            .chain(fds.iter().filter_map(|&(_, resource)| resource))
+            .any(|path| self.paths.iter().any(|re| re.is_match(path)));
+        let by_fd = match self.fds {
+            Some(ref set) => fds.iter().any(|&(fd, _)| set.contains(fd)),
+            None => true,
+        };
+        by_path && by_fd
+    }
+
+
+    /// `traced()` checks if a syscall with the given name is selected by the filter.
+    pub fn traced(&self, name: &str) -> bool {
+        let mut traced = match self.items.first() {
+            Some(&(negated, _)) => negated,
+            None => true,
+        };
+        for (negated, matcher) in &self.items {
+            if matcher.matches(name) {
+                traced = !negated;
+            }
+        }
+        traced
+    }
+}
+
+
+/// `glob_regex()` translates a glob pattern into an anchored regex. A `[` without a
+/// closing `]` is taken literally, as is a `]` leading a set, as in the shell.
+fn glob_regex(pattern: &str) -> String {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut re = String::from("^");
+    let mut idx = 0;
+    while idx < chars.len() {
+        match chars[idx] {
+            '*' if chars.get(idx + 1) == Some(&'*') => {
+                idx += 1;
+                if chars.get(idx + 1) == Some(&'/') {
+                    idx += 1;
+                    re.push_str("(?:.*/)?");
+                } else {
+                    re.push_str(".*");
+                }
+            },
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            '[' => {
+                let mut end = idx + 1;
+                if chars.get(end) == Some(&'!') {
+                    end += 1;
+                }
+                if chars.get(end) == Some(&']') {
+                    end += 1;
+                }
+                match chars[end.min(chars.len())..].iter().position(|&c| c == ']') {
+                    Some(len) => {
+                        end += len;
+                        re.push('[');
+                        let mut set = &chars[idx + 1..end];
+                        if set.first() == Some(&'!') {
+                            re.push('^');
+                            set = &set[1..];
+                        }
+
+                        // the set is copied as is, other than what regex sets treat specially
+                        for &c in set {
+                            if let '\\' | '[' | ']' | '&' | '~' = c {
+                                re.push('\\');
+                            }
+                            re.push(c);
+                        }
+                        re.push(']');
+                        idx = end;
+                    },
+                    None => re.push_str("\\["),
+                }
+            },
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+        idx += 1;
+    }
+    re.push('$');
+    re
+}
+
+
+/// Selects file descriptors by number, with items applied in order like those of a
+/// `Filter`, such that `all,!0` selects every descriptor other than stdin. No
+/// descriptor is selected if no items are given.
+#[derive(Default)]
+pub struct FdSet {
+    items: Vec<(bool, Option<i32>)>,
+}
+
+
+impl FdSet {
+
+    /// `add()` parses a descriptor set expression and appends its items to the set.
+    pub fn add(&mut self, expr: &str) -> Result<(), String> {
+        for item in expr.split(',').filter(|item| !item.is_empty()) {
+            let (negated, item) = match item.strip_prefix('!') {
+                _ if item == "none" => (true, "all"),
+                Some(item) => (true, item),
+                None => (false, item),
+            };
+            let fd = match item {
+                "all" => None,
+                fd => match fd.parse::<i32>() {
+                    Ok(fd) if fd >= 0 => Some(fd),
+                    _ => return Err(format!("invalid descriptor '{}'", fd)),
+                },
+            };
+            self.items.push((negated, fd));
+        }
+        Ok(())
+    }
+
+
+    /// `contains()` checks if a descriptor is selected by the set.
+    pub fn contains(&self, fd: i32) -> bool {
+        let mut selected = match self.items.first() {
+            Some(&(negated, _)) => negated,
+            None => false,
+        };
+        for &(negated, item) in &self.items {
+            if item.is_none_or(|item| item == fd) {
+                selected = !negated;
+            }
+        }
+        selected
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    /// `parse()` builds a filter from a series of expressions.
+    fn parse(exprs: &[&str]) -> Filter {
+        let mut filter = Filter::default();
+        for expr in exprs {
+            filter.add(expr).unwrap();
+        }
+        filter
+    }
+
+
+    #[test]
+    fn traces_everything_by_default() {
+        let filter = parse(&[]);
+        assert!(filter.traced("openat"));
+        assert!(filter.traced("syscall_0x1c3"));
+    }
+
+
+    #[test]
+    fn names() {
+        let filter = parse(&["open,openat"]);
+        assert!(filter.traced("open"));
+        assert!(filter.traced("openat"));
+        assert!(!filter.traced("read"));
+    }
+
+
+    #[test]
+    fn classes() {
+        let filter = parse(&["%file"]);
+        assert!(filter.traced("openat"));
+        assert!(!filter.traced("read"));
+
+        let filter = parse(&["%memory,%net"]);
+        assert!(filter.traced("mmap"));
+        assert!(filter.traced("connect"));
+        assert!(!filter.traced("openat"));
+    }
+
+
+    #[test]
+    fn regex() {
+        let filter = parse(&["/^(read|write)$"]);
+        assert!(filter.traced("read"));
+        assert!(filter.traced("write"));
+        assert!(!filter.traced("readv"));
+    }
+
+
+    #[test]
+    fn negation() {
+        let filter = parse(&["!mmap"]);
+        assert!(!filter.traced("mmap"));
+        assert!(filter.traced("read"));
+
+        let filter = parse(&["!%file"]);
+        assert!(!filter.traced("openat"));
+        assert!(filter.traced("read"));
+
+        let filter = parse(&["none"]);
+        assert!(!filter.traced("read"));
+    }
+
+
+    #[test]
+    fn later_items_take_precedence() {
+        let filter = parse(&["%file,!openat"]);
+        assert!(filter.traced("open"));
+        assert!(!filter.traced("openat"));
+
+        let filter = parse(&["!all", "read"]);
+        assert!(filter.traced("read"));
+        assert!(!filter.traced("write"));
+    }
+
+
+    #[test]
+    fn invalid_items() {
+        let mut filter = Filter::default();
+        assert_eq!(filter.add("%bogus").unwrap_err(), "invalid syscall class '%bogus'");
+        assert_eq!(filter.add("not_a_syscall").unwrap_err(), "invalid syscall 'not_a_syscall'");
+        assert!(filter.add("/(").unwrap_err().starts_with("invalid regex '('"));
+        assert!(filter.add("!%").is_err());
+    }
+
+
+    /// `fds()` builds a descriptor set from an expression.
+    fn fds(expr: &str) -> FdSet {
+        let mut fds = FdSet::default();
+        fds.add(expr).unwrap();
+        fds
+    }
+
+
+    #[test]
+    fn fd_sets() {
+        assert!(!fds("").contains(0));
+        assert!(fds("all").contains(0));
+        assert!(!fds("none").contains(0));
+
+        let set = fds("1,3");
+        assert!(set.contains(1) && set.contains(3));
+        assert!(!set.contains(0) && !set.contains(2));
+
+        // a leading negation selects every other descriptor
+        let set = fds("!0");
+        assert!(!set.contains(0) && set.contains(1));
+        let set = fds("all,!2,!3");
+        assert!(set.contains(1) && !set.contains(2) && !set.contains(3));
+        let set = fds("!all,4");
+        assert!(set.contains(4) && !set.contains(5));
+
+        for expr in &["x", "-1", "1,,y", "!"] {
+            assert!(FdSet::default().add(expr).is_err(), "{}", expr);
+        }
+    }
+
+
+    /// `matches()` checks if a path is selected by a glob pattern.
+    fn matches(pattern: &str, path: &str) -> bool {
+        let mut filter = Filter::default();
+        filter.add_path(pattern).unwrap();
+        filter.selects(&[path.to_string()], &[])
+    }
+
+
+    #[test]
+    fn globs() {
+        assert!(matches("/etc/*.conf", "/etc/host.conf"));
+        assert!(!matches("/etc/*.conf", "/etc/ssl/openssl.conf"));
+        assert!(matches("/dev/tty?", "/dev/tty1"));
+        assert!(!matches("/dev/tty?", "/dev/tty10"));
+        assert!(!matches("/tmp/a?b", "/tmp/a/b"));
+        assert!(matches("/tmp/a.b", "/tmp/a.b"));
+        assert!(!matches("/tmp/a.b", "/tmp/axb"));
+    }
+
+
+    #[test]
+    fn glob_sets() {
+        assert!(matches("/dev/sd[a-c]", "/dev/sdb"));
+        assert!(!matches("/dev/sd[a-c]", "/dev/sdd"));
+        assert!(matches("/tmp/[!x]", "/tmp/y"));
+        assert!(!matches("/tmp/[!x]", "/tmp/x"));
+
+        // a leading `]` is a member of the set, and a `[` without a `]` is literal
+        assert!(matches("/tmp/[]x]", "/tmp/]"));
+        assert!(matches("/tmp/[!]]", "/tmp/a"));
+        assert!(!matches("/tmp/[!]]", "/tmp/]"));
+        assert!(matches("/tmp/[", "/tmp/["));
+        assert!(matches(r"/tmp/[\[&]", "/tmp/&"));
+        assert!(matches(r"/tmp/[\[&]", r"/tmp/\"));
+    }
+
+
+    #[test]
+    fn glob_across_components() {
+        assert!(matches("/tmp/**/f", "/tmp/f"));
+        assert!(matches("/tmp/**/f", "/tmp/a/f"));
+        assert!(matches("/tmp/**/f", "/tmp/a/b/f"));
+        assert!(!matches("/tmp/**/f", "/tmp/af"));
+        assert!(matches("/tmp/**", "/tmp/a/b"));
+        assert!(!matches("/tmp/**", "/var/tmp"));
+    }
+}
@@ -3,6 +3,8 @@
 //!     JtraceLogger logging facility implementation
 //!     for CLI verbosity.
 
+use std::io::{self, Write};
+
 use log::{Record, Level, Metadata};
 
 
@@ -21,13 +23,14 @@ impl log::Log for JtraceLogger {
         // determine prefix from log level
         let prefix = match record.level() {
             Level::Error    => "[ERROR] ",
+            Level::Warn     => "[WARN] ",
             Level::Info     => "[INFO] ",
             _               => "[DEBUG] ",
         };
 
-        // will always output
+        // will always output, unless stderr has gone away along with whatever read it
         if self.enabled(record.metadata()) {
-            println!("{}{}", prefix, record.args());
+            let _ = writeln!(io::stderr(), "{}{}", prefix, record.args());
         }
     }
 
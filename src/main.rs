@@ -6,7 +6,9 @@
 
 #[cfg(all(target_os = "linux",
           any(target_arch = "x86",
-              target_arch = "x86_64")),
+              target_arch = "x86_64",
+              target_arch = "aarch64",
+              target_arch = "riscv64")),
 )]
 extern crate libc;
 extern crate clap;
@@ -14,13 +16,20 @@ extern crate nix;
 extern crate regex;
 extern crate serde;
 extern crate serde_json;
+extern crate base64;
 
+#[macro_use] extern crate bitflags;
 #[macro_use] extern crate log;
 #[macro_use] extern crate lazy_static;
 
-use std::io;
-use std::process::Command;
+use std::io::{self, Write};
+use std::env;
+use std::fs::{self, File};
+use std::mem;
+use std::process::{self, Command};
 use std::ffi::CString;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use libc::{pid_t, c_int};
 
@@ -34,168 +43,756 @@ mod logger;
 use logger::JtraceLogger;
 
 mod ptrace;
-use ptrace::consts::{options, regs};
+use ptrace::consts::{events, regsets};
+use ptrace::consts::options::PtraceOptions;
 use ptrace::helpers;
 
+mod arch;
+use arch::Arch;
+use arch::regs::Regs;
+use arch::info::SyscallInfo;
+
+mod sysent;
+use sysent::StrArg;
+
+mod args;
+use args::ArgType;
+
+mod structs;
+use structs::{Dir, Count};
+
+mod fds;
+
 mod syscall;
-use syscall::SyscallManager;
+use syscall::{Syscall, SyscallManager, Buffer, HexStyle};
+
+mod filter;
+use filter::{Filter, FdSet};
+
+mod event;
+use event::{Event, SignalEvent, ExitEvent, PersonalityEvent};
+
+mod summary;
+
+mod sink;
+use sink::{EventSink, TextSink, TextOptions, Timestamps, JsonLinesSink, JsonDocumentSink, PerProcessSink, SummarySink};
 
 static LOGGER: JtraceLogger = JtraceLogger;
 
 
+/// set by the signal handler when the user requests jtrace to stop tracing, upon which
+/// any attached processes are detached and left running.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+
+/// maximum number of bytes captured from a buffer that is dumped in full, as its length
+/// is taken from the tracee
+const DUMP_LIMIT: usize = 1 << 20;
+
+
+/// `Tracee` stores the state kept for each traced thread between stops.
+struct Tracee {
+
+    /// thread group (process) the thread belongs to
+    tgid: pid_t,
+
+    /// set between SYS_ENTER and SYS_EXIT
+    in_syscall: bool,
+
+    /// architecture the thread made its last syscall through
+    arch: Arch,
+
+    /// syscall that has been entered and is awaiting SYS_EXIT, unless it is filtered out
+    pending: Option<Syscall>,
+
+    /// set for automatically attached children that have yet to report their initial stop
+    awaiting_start: bool,
+}
+
+
+impl Tracee {
+
+    fn new(tgid: pid_t) -> Self {
+        Self { tgid, in_syscall: false, arch: Arch::native(), pending: None, awaiting_start: false }
+    }
+}
+
+
+/// `DecodeOptions` controls how the tracee memory that arguments point to is decoded.
+struct DecodeOptions {
+
+    /// maximum length of string arguments and buffers to show
+    string_limit: usize,
+
+    /// how strings and buffers are escaped
+    hex: HexStyle,
+
+    /// descriptors whose reads are captured in full, up to `DUMP_LIMIT`, and shown as hexdumps
+    dump_reads: FdSet,
+
+    /// descriptors whose writes are captured in full, up to `DUMP_LIMIT`, and shown as hexdumps
+    dump_writes: FdSet,
+}
+
+
 /// `Parent` provides an interface for initializing
-/// and interacting with a specified PID. It implements
+/// and interacting with a set of traced PIDs. It implements
 /// internal controls and establishes helpers for syscalls
 /// that are needed for tracer/tracee interactions.
 struct Parent {
-    pid: pid_t,
+    tracees: HashMap<pid_t, Tracee>,
+    leader: Option<pid_t>,
+    exit_status: c_int,
     manager: SyscallManager,
-    out_json: bool
+    decode: DecodeOptions,
+    follow: bool,
+
+    /// set if a snapshot of the registers is taken at every syscall-stop
+    snapshots: bool,
+
+    /// cleared once PTRACE_GET_SYSCALL_INFO turns out to be unsupported by the kernel
+    syscall_info: bool
 }
 
 
 impl Parent {
 
-    /// `new()` initializes new Parent interface with PID and system call manager that stores
-    /// parsed system calls
-    fn new(pid: pid_t, out_json: bool) -> Self {
-        let manager = SyscallManager::new();
-        Self { pid, manager, out_json }
+    /// `new()` initializes new Parent interface with the system call manager that traced
+    /// events are passed to, how arguments are decoded from tracee memory, whether to
+    /// follow children created through fork, vfork and clone, and whether to record the
+    /// registers of every syscall.
+    fn new(manager: SyscallManager, decode: DecodeOptions, follow: bool, snapshots: bool) -> Self {
+        Self {
+            tracees: HashMap::new(),
+            leader: None,
+            exit_status: 0,
+            manager,
+            decode,
+            follow,
+            snapshots,
+            syscall_info: true
+        }
+    }
+
+
+    /// `options()` returns the ptrace options to set on every tracee. Exec is always traced,
+    /// so that it is reported as an event rather than with a SIGTRAP.
+    fn options(&self) -> PtraceOptions {
+        let mut opts = PtraceOptions::TRACESYSGOOD | PtraceOptions::TRACEEXEC;
+        if self.follow {
+            opts |= PtraceOptions::TRACEFORK | PtraceOptions::TRACEVFORK | PtraceOptions::TRACECLONE;
+        }
+        opts
     }
 
 
-    /// `run()` instantiates the loop that goes through program execution, waiting and stepping
-    /// through each syscall and properly handling errors when necessary.
-    fn run(&mut self) -> io::Result<()> {
-        info!("Looping through process syscalls.");
-        loop {
-            match self.step() {
-                Err(e) => panic!("Unable to run tracer. Reason: {:?}", e),
-                Ok(Some(status)) => {
-                    if status == 0 {
-                        break;
-                    } else {
-                        debug!("Status reported: {:?}", status);
-                    }
+    /// `trace_child()` waits for a forked child that called PTRACE_TRACEME to stop itself,
+    /// and then sets trace options on it, such that it is ready to be stepped through.
+    fn trace_child(&mut self, pid: pid_t) -> io::Result<()> {
+        info!("Waiting for child process to send SIGSTOP");
+        Parent::wait_stop(pid)?;
+
+        // children we spawned should not outlive us if we exit unexpectedly
+        info!("Setting trace options with PTRACE_SETOPTIONS");
+        helpers::set_options(pid, self.options() | PtraceOptions::EXITKILL)?;
+        self.tracees.insert(pid, Tracee::new(pid));
+        self.manager.seed_fds(pid);
+        self.leader = Some(pid);
+        Ok(())
+    }
+
+
+    /// `attach()` seizes every thread of an already running process and interrupts them, such
+    /// that they are stopped and ready to be stepped through once `run()` is called. Threads
+    /// that exit before they are seized are skipped.
+    fn attach(&mut self, pid: pid_t) -> io::Result<()> {
+        let tids = Parent::threads(pid).unwrap_or_else(|_| vec![pid]);
+        for tid in tids {
+            info!("Seizing thread {} of process {} with PTRACE_SEIZE", tid, pid);
+            match helpers::seize(tid, self.options()) {
+                Err(ref e) if tid != pid => {
+                    debug!("Unable to seize thread {}: {}", tid, e);
+                    continue;
                 },
-                other => { other?; }
+                result => result?,
+            }
+            helpers::interrupt(tid)?;
+            if Parent::wait_stop(tid)?.is_some() {
+                self.tracees.insert(tid, Tracee::new(pid));
             }
         }
-        self.output();
+        self.manager.seed_fds(pid);
         Ok(())
     }
 
-    /// `output()` is called after a run in order return trace results the configured format,
-    /// either as raw unstructured trace or in JSON
-    /// TODO: support other formats to deserialize
-    fn output(&mut self) -> () {
 
-        // JSON
-        if self.out_json {
-            println!("{}", self.manager.to_json().expect("unable to output to JSON"));
+    /// `add_child()` starts tracking a new child reported through a fork, vfork or clone
+    /// event, unless its initial stop was already seen before the event itself.
+    fn add_child(&mut self, pid: pid_t) {
+        if self.tracees.contains_key(&pid) {
+            return;
         }
+        let mut tracee = Tracee::new(Parent::tgid(pid));
+        tracee.awaiting_start = true;
+        self.tracees.insert(pid, tracee);
+    }
+
 
-        // Default raw output
-        else {
-            println!("{}", self.manager);
+    /// `detach_all()` stops every tracee and detaches from it, leaving it running. Syscalls
+    /// that tracees were in the middle of are written out as unfinished. Tracees known to be
+    /// in a ptrace-stop already, such as one whose stop could not be handled, are detached
+    /// as they are. Failing to write out or detach from one tracee does not keep the rest
+    /// from being detached, and the first error is returned.
+    fn detach_all(&mut self, stopped: &[pid_t]) -> io::Result<()> {
+        let mut result = Ok(());
+        let tracees: Vec<(pid_t, Tracee)> = self.tracees.drain().collect();
+        for (pid, tracee) in tracees {
+            info!("Detaching from process {}", pid);
+            if let Some(syscall) = tracee.pending {
+                if let Err(e) = self.manager.emit(Event::Syscall(syscall)) {
+                    result = result.and(Err(e));
+                }
+            }
+            let detached = if stopped.contains(&pid) {
+                helpers::detach(pid, 0)
+            } else {
+                Parent::stop_and_detach(pid, tracee.tgid)
+            };
+            if let Err(e) = detached {
+                warn!("Unable to detach from process {}: {}", pid, e);
+                result = result.and(Err(e));
+            }
         }
+        result
     }
 
 
-    /// `step()` defines the main instrospection performed ontop of the traced process, using
-    /// ptrace to parse out syscall registers for output.
-    fn step(&mut self) -> io::Result<Option<c_int>> {
+    /// `stop_and_detach()` brings a running tracee into a ptrace-stop, which it must be in
+    /// to be detached, and detaches from it. Seized tracees are stopped with PTRACE_INTERRUPT.
+    /// Others, such as the child we spawned and its descendants, are sent a SIGSTOP instead,
+    /// and are stepped until it is about to be delivered, at which point it is suppressed.
+    fn stop_and_detach(pid: pid_t, tgid: pid_t) -> io::Result<()> {
+        let seized = helpers::interrupt(pid).is_ok();
+        if !seized && unsafe { libc::syscall(libc::SYS_tgkill, tgid, pid, libc::SIGSTOP) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        loop {
+            let status = match Parent::wait_stop(pid)? {
+                Some(status) => status,
+                None => return Ok(()),
+            };
 
-        info!("ptrace-ing with PTRACE_SYSCALL to SYS_ENTER");
-        helpers::syscall(self.pid)?;
-        if let Some(status) = self.wait().unwrap() {
-            return Ok(Some(status));
+            // re-deliver a signal that was about to be delivered, rather than one of our traps
+            let signal = libc::WSTOPSIG(status);
+            let trap = signal == libc::SIGTRAP || signal == libc::SIGTRAP | 0x80 || status >> 16 != 0;
+            match signal {
+                _ if seized => return helpers::detach(pid, if trap { 0 } else { signal.into() }),
+                libc::SIGSTOP if !trap => return helpers::detach(pid, 0),
+                _ if !trap => helpers::syscall(pid, signal.into())?,
+                _ => helpers::syscall(pid, 0)?,
+            };
         }
+    }
 
-        // determine syscall number and initialize
-        let syscall_num = match self.get_syscall_num() {
-            Ok(num) => num,
-            Err(e) => panic!("Cannot retrieve syscall number. Reason {:?}", e),
-        };
-        debug!("Syscall number: {:?}", syscall_num);
 
-        // retrieve first 3 arguments from syscall
-        let mut args: Vec<u64> = Vec::new();
-        for i in 0..2 {
-            args.push(self.get_arg(i).unwrap());
+    /// `run()` instantiates the loop that goes through program execution, waiting and stepping
+    /// through each syscall and properly handling errors when necessary. Returns the exit
+    /// status of the spawned child, or 0 when attached to already running processes.
+    fn run(&mut self) -> io::Result<c_int> {
+        info!("Looping through process syscalls.");
+
+        // all tracees begin stopped, so set them off to their first SYS_ENTER
+        for pid in self.tracees.keys() {
+            helpers::syscall(*pid, 0)?;
         }
 
-        // add syscall to manager
-        self.manager.add_syscall(syscall_num, args);
+        while !self.tracees.is_empty() {
+            if INTERRUPTED.load(Ordering::SeqCst) {
+                self.detach_all(&[])?;
+                break;
+            }
+
+            let (pid, status) = match self.wait()? {
+                Some(stop) => stop,
+                None => continue,
+            };
+
+            // record and stop tracking processes that have terminated
+            if libc::WIFEXITED(status) || libc::WIFSIGNALED(status) {
+                debug!("Process {} terminated with status {:?}", pid, status);
+                self.terminate(pid, status)?;
+                continue;
+            }
+
+            // new children report an initial stop that is not a syscall, and may do so
+            // before the event in their parent is reported
+            let starting = match self.tracees.get_mut(&pid) {
+                Some(tracee) => mem::replace(&mut tracee.awaiting_start, false),
+                None => {
+                    debug!("New child {} reported before its parent's event", pid);
+                    self.tracees.insert(pid, Tracee::new(Parent::tgid(pid)));
+                    true
+                }
+            };
+            if starting {
 
-        info!("ptrace-ing with PTRACE_SYSCALL to SYS_EXIT");
-        helpers::syscall(self.pid)?;
-        if let Some(status) = self.wait().unwrap() {
-            return Ok(Some(status));
+                // new processes begin with a copy of the descriptors of their parent
+                if self.tracees.get(&pid).is_some_and(|tracee| tracee.tgid == pid) {
+                    self.manager.seed_fds(pid);
+                }
+                self.resume(pid, 0);
+                continue;
+            }
+
+            // leave the remaining tracees running rather than killing them along with us
+            if let Err(e) = self.handle_stop(pid, status) {
+                error!("Unable to handle stop of process {}, detaching: {}", pid, e);
+                let _ = self.detach_all(&[pid]);
+                let _ = self.manager.finish();
+                return Err(e);
+            }
         }
-        Ok(None)
+        self.manager.finish()?;
+        Ok(self.exit_status)
     }
 
 
-    /// `wait()` wrapper to waitpid/wait4, with error-checking in order
-    /// to return proper type back to developer.
-    fn wait(&self) -> io::Result<Option<c_int>> {
-        let mut status = 0;
-        unsafe {
-            libc::waitpid(self.pid, &mut status, 0);
+    /// `terminate()` records the termination of a tracee, along with the syscall it was in
+    /// the middle of, such as `exit_group`. If it is the child we spawned, its exit code is
+    /// kept to be returned as our own, with signals mapped to 128 + the signal number as in
+    /// the shell.
+    fn terminate(&mut self, pid: pid_t, status: c_int) -> io::Result<()> {
+        let tgid = match self.tracees.remove(&pid) {
+            Some(tracee) => {
+                if let Some(syscall) = tracee.pending {
+                    self.manager.emit(Event::Syscall(syscall))?;
+                }
+                tracee.tgid
+            },
+            None => pid,
+        };
+        self.manager.emit(Event::Exit(ExitEvent::new(tgid, pid, status)))?;
+        if tgid == pid {
+            self.manager.drop_fds(pid);
+        }
 
-            // error-check status set
-            if libc::WIFEXITED(status) {
-                Ok(Some(libc::WEXITSTATUS(status)))
+        if self.leader == Some(pid) {
+            self.exit_status = if libc::WIFEXITED(status) {
+                libc::WEXITSTATUS(status)
             } else {
-                Ok(None)
+                128 + libc::WTERMSIG(status)
+            };
+        }
+        Ok(())
+    }
+
+    /// `handle_stop()` determines why a tracee stopped and handles it accordingly, before
+    /// resuming it. Syscall-stops are distinguished from signal-delivery-stops by the bit
+    /// set through PTRACE_O_TRACESYSGOOD, and ptrace events are reported in the upper bits
+    /// of the status. Delivered signals are recorded and then re-injected into the tracee.
+    fn handle_stop(&mut self, pid: pid_t, status: c_int) -> io::Result<()> {
+        let signal = libc::WSTOPSIG(status);
+        let event = status >> 16;
+        let tgid = self.tracees.get(&pid).map_or(pid, |tracee| tracee.tgid);
+
+        // syscall-stop, either on SYS_ENTER or SYS_EXIT
+        if signal == libc::SIGTRAP | 0x80 {
+            self.step(pid)?;
+            self.resume(pid, 0);
+            return Ok(());
+        }
+
+        // ptrace events
+        match event {
+            0 => {},
+
+            // fork, vfork and clone events report the new child's PID, and are followed
+            // by the SYS_EXIT of the syscall that created it
+            events::PTRACE_EVENT_FORK | events::PTRACE_EVENT_VFORK | events::PTRACE_EVENT_CLONE => {
+                let child = helpers::get_event_msg(pid)? as pid_t;
+                info!("Process {} created child {}", pid, child);
+                self.add_child(child);
+                self.resume(pid, 0);
+                return Ok(());
+            },
+
+            // on exec by a non-leader thread, the thread assumes the ID of the leader, so move
+            // over the state of the former thread, which includes the pending execve
+            events::PTRACE_EVENT_EXEC => {
+                let former = helpers::get_event_msg(pid)? as pid_t;
+                if former != pid {
+                    if let Some(tracee) = self.tracees.remove(&former) {
+                        self.tracees.insert(pid, tracee);
+                    }
+                }
+                self.resume(pid, 0);
+                return Ok(());
+            },
+
+            // seized tracees report group-stops as PTRACE_EVENT_STOP with the stopping
+            // signal, and are kept stopped with PTRACE_LISTEN until continued
+            events::PTRACE_EVENT_STOP => {
+                if Parent::is_stop_signal(signal) {
+                    self.manager.emit(Event::Signal(SignalEvent::stopped(tgid, pid, signal)))?;
+                    if let Err(e) = helpers::listen(pid) {
+                        debug!("Unable to listen on process {}: {:?}", pid, e);
+                    }
+                } else {
+                    self.resume(pid, 0);
+                }
+                return Ok(());
+            },
+            _ => {
+                self.resume(pid, 0);
+                return Ok(());
+            }
+        }
+
+        // signal-delivery-stop, which is told apart from a group-stop of a tracee that was not
+        // seized by whether PTRACE_GETSIGINFO succeeds
+        match helpers::get_siginfo(pid) {
+            Ok(info) => {
+                self.manager.emit(Event::Signal(SignalEvent::delivered(tgid, pid, &info)))?;
+                self.resume(pid, signal);
+            },
+            Err(ref e) if e.raw_os_error() == Some(libc::EINVAL) => {
+                self.manager.emit(Event::Signal(SignalEvent::stopped(tgid, pid, signal)))?;
+                self.resume(pid, 0);
+            },
+            Err(e) => {
+                debug!("Unable to retrieve siginfo for process {}: {:?}", pid, e);
+                self.resume(pid, signal);
             }
         }
+        Ok(())
+    }
+
+
+    /// `resume()` continues a stopped tracee until its next syscall-stop, injecting a
+    /// signal if non-zero. Tracees may have been killed while stopped, in which case
+    /// their termination is reported later, so failures are not fatal.
+    fn resume(&self, pid: pid_t, signal: c_int) {
+        if let Err(e) = helpers::syscall(pid, signal.into()) {
+            debug!("Unable to resume process {}: {:?}", pid, e);
+        }
     }
 
 
-    /// `get_arg()` is called to introspect current process
-    /// states register values in order to determine syscall
-    /// and arguments passed.
-    fn get_arg(&mut self, reg: u8) -> io::Result<u64> {
-
-        #[cfg(target_arch = "x86_64")]
-        let offset = match reg {
-            0 => regs::RDI,
-            1 => regs::RSI,
-            2 => regs::RDX,
-            3 => regs::RCX,
-            4 => regs::R8,
-            5 => regs::R9,
-            _ => panic!("Unmatched argument offset")
+    /// `is_stop_signal()` checks if a signal puts a process into a group-stop.
+    fn is_stop_signal(signal: c_int) -> bool {
+        signal == libc::SIGSTOP || signal == libc::SIGTSTP
+            || signal == libc::SIGTTIN || signal == libc::SIGTTOU
+    }
+
+
+    /// `step()` defines the main instrospection performed ontop of the traced process, using
+    /// ptrace to parse out syscall registers for output. Each tracee alternates between
+    /// SYS_ENTER and SYS_EXIT, and syscalls that are filtered out are skipped on SYS_ENTER
+    /// before any of their arguments are decoded.
+    fn step(&mut self, pid: pid_t) -> io::Result<()> {
+        let (in_syscall, pending) = match self.tracees.get_mut(&pid) {
+            Some(tracee) => (mem::replace(&mut tracee.in_syscall, false), tracee.pending.take()),
+            None => (false, None),
         };
 
-        /* TODO: implement registers
-        #[cfg(target_arch = "x86")]
-        let offset = match reg {
-            0 => regs::EDI,
-            1 => regs::ESI,
-            2 => regs::EDX,
-            3 => regs::ECX,
-            4 => regs::E8,
-            5 => regs::E9,
-            _ => panic!("Unmatched argument offset")
+        let (arch, ip, syscall_num, args) = match self.get_syscall_info(pid, !in_syscall)? {
+
+            // on SYS_EXIT, retrieve return value and complete the syscall
+            SyscallInfo::Exit { retval, is_error, .. } => {
+                if let Some(mut syscall) = pending {
+                    debug!("Syscall return value: {:?} (error: {})", retval, is_error);
+                    if self.snapshots {
+                        syscall.set_exit_regs(self.get_regs(pid)?);
+                    }
+                    syscall.set_return(retval);
+                    if !syscall.failed() && !syscall.suppressed() {
+                        Parent::read_structs(pid, &mut syscall, Dir::Out);
+                        self.read_buffers(pid, &mut syscall, Dir::Out);
+                    }
+                    self.manager.update_fds(&mut syscall);
+                    self.manager.emit(Event::Syscall(syscall))?;
+                }
+                return Ok(());
+            },
+            SyscallInfo::Entry { arch, ip, num, args } => (arch, ip, num, args),
         };
-        */
 
-        helpers::peek_user(self.pid, offset).map(|x| x as u64)
+        // a missed SYS_EXIT leaves the previous syscall unfinished
+        if let Some(syscall) = pending {
+            self.manager.emit(Event::Syscall(syscall))?;
+        }
+
+        // report when the architecture the syscall is made through changes
+        let tgid = self.tracees.get(&pid).map_or(pid, |tracee| tracee.tgid);
+        if let Some(tracee) = self.tracees.get_mut(&pid) {
+            tracee.in_syscall = true;
+            if mem::replace(&mut tracee.arch, arch) != arch {
+                self.manager.emit(Event::Personality(PersonalityEvent::new(tgid, pid, arch)))?;
+            }
+        }
+
+        // syscalls that are not traced are still followed if descriptors are tracked
+        debug!("Syscall number: {:?} at {:#x}", syscall_num, ip);
+        let traced = self.manager.traced(arch, syscall_num);
+        if !traced && !self.manager.tracks_fds() {
+            return Ok(());
+        }
+
+        // keep as many arguments as the syscall actually takes
+        let nargs = self.manager.nargs(arch, syscall_num);
+        let args: Vec<u64> = args.iter().take(nargs).cloned().collect();
+
+        // initialize syscall, and decode any string arguments from tracee memory
+        // syscalls that do not refer to the selected paths and descriptors are only
+        // followed for their effect on descriptors, like those that are not traced
+        let mut syscall = self.manager.new_syscall(tgid, pid, arch, syscall_num, args);
+        let paths = if traced { self.read_strings(pid, &mut syscall) } else { Vec::new() };
+        if !traced || !self.manager.selected(&syscall, &paths) {
+            syscall.suppress();
+        } else {
+            if self.snapshots {
+                syscall.set_entry_regs(self.get_regs(pid)?);
+            }
+            Parent::read_structs(pid, &mut syscall, Dir::In);
+            self.read_buffers(pid, &mut syscall, Dir::In);
+        }
+
+        if let Some(tracee) = self.tracees.get_mut(&pid) {
+            tracee.pending = Some(syscall);
+        }
+        Ok(())
+    }
+
+
+    /// `read_strings()` decodes the string arguments of a syscall from tracee memory, and
+    /// returns the paths among them. Relative paths are resolved against the directory
+    /// descriptor that precedes them if it is known, or the working directory otherwise.
+    fn read_strings(&self, pid: pid_t, syscall: &mut Syscall) -> Vec<String> {
+        let mut paths = Vec::new();
+        for &(arg, kind) in sysent::string_args(syscall.name()) {
+            let addr = match syscall.args().get(arg) {
+                Some(&addr) if addr != 0 => addr,
+                _ => continue,
+            };
+            let limit = match kind {
+                StrArg::Path => sysent::PATH_MAX,
+                StrArg::Str => self.decode.string_limit,
+            };
+            let (bytes, truncated) = match helpers::read_string(pid, addr, limit) {
+                Ok(string) => string,
+                Err(e) => {
+                    debug!("Unable to read string argument at {:#x}: {:?}", addr, e);
+                    continue;
+                }
+            };
+            if kind == StrArg::Path {
+                let path = String::from_utf8_lossy(&bytes).into_owned();
+                let dirfd = arg.checked_sub(1)
+                               .filter(|&idx| matches!(args::arg_type(syscall.name(), idx), ArgType::DirFd));
+                let dir = match dirfd.and_then(|idx| syscall.fd(idx)) {
+                    _ if path.starts_with('/') => None,
+                    Some(dir) => Some(dir.to_string()),
+                    None => fs::read_link(format!("/proc/{}/cwd", pid))
+                        .ok()
+                        .map(|cwd| cwd.to_string_lossy().into_owned()),
+                };
+                paths.push(match dir {
+                    Some(dir) => format!("{}/{}", dir.trim_end_matches('/'), path),
+                    None => path,
+                });
+            }
+            syscall.set_string(arg, &bytes, truncated, self.decode.hex);
+        }
+        paths
     }
 
 
-    /// `get_syscall_num()` uses ptrace with PEEK_USER to return the
-    /// syscall num from ORIG_RAX.
-    fn get_syscall_num(&mut self) -> io::Result<u64> {
-        helpers::peek_user(self.pid, regs::ORIG_RAX).map(|x| x as u64)
+    /// `read_structs()` decodes the structures that arguments of a syscall point to and
+    /// that are passed in the given direction from tracee memory. Arguments that cannot
+    /// be read are left to be shown as addresses, and structures the kernel updated are
+    /// kept apart from what was passed in.
+    fn read_structs(pid: pid_t, syscall: &mut Syscall, dir: Dir) {
+        for (arg, ty, declared, count) in args::structs(syscall.name(), dir) {
+            let addr = match syscall.args().get(arg) {
+                Some(&addr) if addr != 0 => addr,
+                _ => continue,
+            };
+            let count = match count {
+                Count::One => None,
+                Count::Arg(idx) => syscall.args().get(idx).cloned(),
+                Count::Ret => syscall.ret().map(|ret| ret as u64),
+            };
+            match structs::read(pid, ty, syscall.arch(), dir, addr, count) {
+                Ok(value) if declared == Dir::InOut && dir == Dir::Out => syscall.set_returned(arg, value),
+                Ok(value) => syscall.set_struct(arg, value),
+                Err(e) => debug!("Unable to read structure at {:#x}: {:?}", addr, e),
+            }
+        }
+    }
+
+
+    /// `read_buffers()` captures the data that arguments of a syscall point to and that is
+    /// transferred in the given direction, which for reads is only known at SYS_EXIT. Only
+    /// as much as is shown is read from tracee memory. Buffers of descriptors that are to
+    /// be dumped in full are captured at SYS_EXIT in either direction, as only the return
+    /// value tells how much was transferred, and no more than `DUMP_LIMIT` is read.
+    fn read_buffers(&self, pid: pid_t, syscall: &mut Syscall, dir: Dir) {
+        let fd = syscall.args().first().map(|&fd| fd as i32);
+        let dumped = |fds: &FdSet| dir == Dir::Out && fd.is_some_and(|fd| fds.contains(fd));
+        let mut buffers: Vec<(usize, Count, bool)> = args::buffers(syscall.name(), dir)
+            .into_iter()
+            .map(|(arg, count)| (arg, count, dumped(&self.decode.dump_reads)))
+            .collect();
+        if dumped(&self.decode.dump_writes) {
+            buffers.extend(args::buffers(syscall.name(), Dir::In).into_iter().map(|(arg, _)| (arg, Count::Ret, true)));
+        }
+
+        for (arg, count, dump) in buffers {
+            let addr = match syscall.args().get(arg) {
+                Some(&addr) if addr != 0 => addr,
+                _ => continue,
+            };
+            let len = match count {
+                Count::One => None,
+                Count::Arg(idx) => syscall.args().get(idx).cloned(),
+                Count::Ret => syscall.ret().map(|ret| ret as u64),
+            };
+            let len = match len {
+                Some(len) => len as usize,
+                None => continue,
+            };
+            let limit = self.decode.string_limit;
+            let read = if dump { len.min(DUMP_LIMIT) } else { len.min(limit) };
+            match helpers::read_memory(pid, addr, read) {
+                Ok(data) => syscall.set_buffer(arg, Buffer::new(data, len, limit, self.decode.hex, dump)),
+                Err(e) => debug!("Unable to read buffer at {:#x}: {:?}", addr, e),
+            }
+        }
+    }
+
+
+    /// `wait()` wrapper to waitpid/wait4 on any tracee, with error-checking in order
+    /// to return proper type back to developer. Returns `None` if interrupted by a signal.
+    fn wait(&self) -> io::Result<Option<(pid_t, c_int)>> {
+        let mut status = 0;
+        let pid = unsafe { libc::waitpid(-1, &mut status, libc::__WALL) };
+        if pid == -1 {
+            let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted {
+                return Ok(None);
+            }
+            return Err(err);
+        }
+        Ok(Some((pid, status)))
+    }
+
+
+    /// `wait_stop()` waits on a single tracee until it enters a stop, returning its status,
+    /// or `None` if it terminated instead.
+    fn wait_stop(pid: pid_t) -> io::Result<Option<c_int>> {
+        let mut status = 0;
+        if unsafe { libc::waitpid(pid, &mut status, libc::__WALL) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        if libc::WIFSTOPPED(status) {
+            Ok(Some(status))
+        } else {
+            Ok(None)
+        }
+    }
+
+
+    /// `tgid()` determines the thread group a thread belongs to from procfs, so that
+    /// new threads can be told apart from new processes.
+    fn tgid(tid: pid_t) -> pid_t {
+        let status = match fs::read_to_string(format!("/proc/{}/status", tid)) {
+            Ok(status) => status,
+            Err(_) => return tid,
+        };
+        status.lines()
+              .find(|line| line.starts_with("Tgid:"))
+              .and_then(|line| line["Tgid:".len()..].trim().parse::<pid_t>().ok())
+              .unwrap_or(tid)
+    }
+
+
+    /// `threads()` lists the IDs of every thread in a process from procfs.
+    fn threads(pid: pid_t) -> io::Result<Vec<pid_t>> {
+        let mut tids = Vec::new();
+        for entry in fs::read_dir(format!("/proc/{}/task", pid))? {
+            if let Ok(tid) = entry?.file_name().to_string_lossy().parse::<pid_t>() {
+                tids.push(tid);
+            }
+        }
+        Ok(tids)
+    }
+
+
+    /// `get_syscall_info()` describes the syscall-stop of a tracee with a single call to
+    /// PTRACE_GET_SYSCALL_INFO, which also tells whether the stop is a SYS_ENTER. On kernels
+    /// lacking it, this falls back to a register snapshot for the rest of the trace, with
+    /// `entry` telling the direction of the stop where the architecture cannot.
+    fn get_syscall_info(&mut self, pid: pid_t, entry: bool) -> io::Result<SyscallInfo> {
+        if self.syscall_info {
+            match helpers::get_syscall_info(pid) {
+                Ok(bytes) => {
+                    if let Some(info) = SyscallInfo::from_bytes(&bytes) {
+                        return Ok(info);
+                    }
+                    debug!("Unexpected syscall info of {} bytes for process {}", bytes.len(), pid);
+                },
+                Err(ref e) if e.raw_os_error() == Some(libc::EIO) => {
+                    info!("PTRACE_GET_SYSCALL_INFO unsupported, falling back to PTRACE_GETREGSET");
+                    self.syscall_info = false;
+                },
+                Err(e) => return Err(e),
+            }
+        }
+        let mut regs = self.get_regs(pid)?;
+
+        // 64-bit tasks can make syscalls through the i386 ABI with `int 0x80`, which the
+        // register set does not tell apart, so look at the instruction that was executed
+        if let Regs::X86_64(ref x86_64) = regs {
+            let insn = helpers::read_memory(pid, x86_64.rip.wrapping_sub(2), 2).unwrap_or_default();
+            if let Some(compat) = regs.compat(&insn) {
+                regs = compat;
+            }
+        }
+        Ok(SyscallInfo::from_regs(&regs, entry))
+    }
+
+
+    /// `get_regs()` retrieves a snapshot of the registers of a tracee stopped at a syscall
+    /// with PTRACE_GETREGSET, in the layout of the architecture it is running as.
+    fn get_regs(&self, pid: pid_t) -> io::Result<Regs> {
+        let bytes = helpers::get_regset(pid, regsets::NT_PRSTATUS)?;
+        Regs::from_bytes(Arch::native(), &bytes).ok_or_else(|| {
+            io::Error::other(format!("unexpected register set of {} bytes", bytes.len()))
+        })
+    }
+}
+
+
+/// `handle_interrupt()` is installed as a signal handler when attached to processes, so
+/// that jtrace can cleanly detach from them rather than terminating with them stopped.
+extern "C" fn handle_interrupt(_: c_int) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+
+/// `run()` runs the tracer to completion, returning the status to exit with, which is
+/// that of the spawned child, or 1 if tracing failed.
+fn run(parent: &mut Parent) -> c_int {
+    match parent.run() {
+        Ok(status) => status,
+        Err(e) => {
+            let _ = writeln!(io::stderr(), "jtrace: unable to run tracer: {}", e);
+            1
+        },
     }
 }
 
 
-#[allow(unused_must_use)]
 fn main() {
     let matches = App::new("jtrace")
         .about("process tracer that outputs deserialized JSON")
@@ -205,16 +802,182 @@ fn main() {
                 .raw(true)
                 .help("Command to analyze as child, including positional arguments.")
                 .takes_value(true)
-                .required(true)
+                .required_unless("pid")
+        )
+        .arg(
+            Arg::with_name("pid")
+                .short("p")
+                .long("pid")
+                .help("Attach to an already running process by PID. Can be repeated to trace several processes.")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .validator(|pid| match pid.parse::<pid_t>() {
+                    Ok(pid) if pid > 0 => Ok(()),
+                    _ => Err("PID must be a positive integer".to_string()),
+                })
+                .conflicts_with("command")
+        )
+        .arg(
+            Arg::with_name("follow")
+                .short("f")
+                .long("follow-forks")
+                .help("Trace child processes and threads created through fork, vfork and clone. \
+                       If given twice with --output, the trace of each process is written to its own file.")
+                .multiple(true)
+                .takes_value(false)
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .help("Write the trace to a file rather than stdout, or to FILE.<pid> for each process with -ff.")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(false)
         )
         .arg(
             Arg::with_name("json")
                 .short("j")
                 .long("json")
-                .help("Output system call trace as JSON.")
+                .help("Output system call trace as a single JSON document with a list of syscalls, completed once \
+                       tracing ends.")
+                .takes_value(false)
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("json_lines")
+                .long("json-lines")
+                .help("Output system call trace as JSON Lines, with one event per line written as it happens.")
+                .takes_value(false)
+                .required(false)
+                .conflicts_with("json")
+        )
+        .arg(
+            Arg::with_name("regs")
+                .long("regs")
+                .help("Include snapshots of the registers at SYS_ENTER and SYS_EXIT of each syscall in JSON output.")
+                .takes_value(false)
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("expr")
+                .short("e")
+                .long("expr")
+                .help("Qualifying expression selecting what to trace, e.g. trace=open,openat,%file,!mmap,/^stat, \
+                       or which descriptors to dump all data read from or written to, e.g. read=3,4 or write=all. \
+                       Data passed through an iovec, as with readv or sendmsg, is not dumped. Can be repeated.")
+                .value_name("EXPR")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("path")
+                .short("P")
+                .long("trace-path")
+                .help("Only output syscalls that refer to a path matching PATTERN, either by name or through a \
+                       descriptor, with * and ? matching within a path component and ** across them. Implies -y. \
+                       Can be repeated.")
+                .value_name("PATTERN")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("fd")
+                .long("fd")
+                .help("Only output syscalls that operate on the given descriptors, e.g. 3,4 or !0. Can be repeated.")
+                .value_name("FDS")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("summary")
+                .short("c")
+                .long("summary-only")
+                .help("Count calls, errors and time spent for each syscall, and only output a summary at exit.")
+                .takes_value(false)
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("summary_trace")
+                .short("C")
+                .long("summary")
+                .help("Like --summary-only, but also output the trace itself before the summary.")
+                .takes_value(false)
+                .required(false)
+                .conflicts_with("summary")
+        )
+        .arg(
+            Arg::with_name("timestamps")
+                .short("t")
+                .long("timestamps")
+                .help("Prefix each line with the time of day. Given twice, include microseconds, and \
+                       given three times, print seconds since the epoch instead.")
+                .multiple(true)
+                .takes_value(false)
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("relative")
+                .short("r")
+                .long("relative-timestamps")
+                .help("Prefix each line with the time elapsed since the previous one.")
+                .takes_value(false)
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("durations")
+                .short("T")
+                .long("syscall-times")
+                .help("Show the time spent in each syscall.")
+                .takes_value(false)
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("string_limit")
+                .short("s")
+                .long("string-limit")
+                .help("Maximum length of string arguments to print (default 32). Paths are always printed in full.")
+                .takes_value(true)
+                .validator(|limit| match limit.parse::<usize>() {
+                    Ok(_) => Ok(()),
+                    Err(_) => Err("string limit must be a non-negative integer".to_string()),
+                })
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("hex")
+                .short("x")
+                .help("Print strings and buffers with non-ASCII characters in hex. Given twice, print them all in hex.")
+                .multiple(true)
+                .takes_value(false)
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("decode_fds")
+                .short("y")
+                .long("decode-fds")
+                .help("Track the descriptors of each process, and print the file, socket or pipe each descriptor \
+                       refers to, e.g. 3</etc/passwd>.")
                 .takes_value(false)
                 .required(false)
         )
+        .arg(
+            Arg::with_name("syscall_header")
+                .long("syscall-header")
+                .help("Resolve syscall numbers by parsing a unistd.h header, such as /usr/include/asm/unistd_64.h, \
+                       rather than with the built-in table.")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(false)
+        )
         .arg(
             Arg::with_name("verbosity")
                 .short("v")
@@ -230,12 +993,152 @@ fn main() {
     let level_filter = match matches.occurrences_of("verbosity") {
         2       => LevelFilter::Debug,
         1       => LevelFilter::Info,
-        0 | _   => LevelFilter::Off,
+        _       => LevelFilter::Off,
     };
     log::set_logger(&LOGGER).expect("unable to initialize logger");
     log::set_max_level(level_filter);
     info!("Initialized logger");
 
+    // parse maximum string length for decoded arguments
+    let string_limit = match matches.value_of("string_limit") {
+        Some(limit) => limit.parse::<usize>().unwrap(),
+        None => 32,
+    };
+
+    // parse qualifying expressions, which apply to the syscalls traced if unqualified
+    let mut filter = Filter::default();
+    let mut dump_reads = FdSet::default();
+    let mut dump_writes = FdSet::default();
+    for expr in matches.values_of("expr").into_iter().flatten() {
+        let (qualifier, value) = match expr.find('=') {
+            Some(idx) => (&expr[..idx], &expr[idx + 1..]),
+            None => ("trace", expr),
+        };
+        let result = match qualifier {
+            "trace" | "t" => filter.add(value),
+            "read" | "reads" | "r" => dump_reads.add(value),
+            "write" | "writes" | "w" => dump_writes.add(value),
+            _ => Err(format!("unknown qualifier '{}'", qualifier)),
+        };
+        if let Err(e) = result {
+            let message = format!("invalid expression '{}': {}", expr, e);
+            clap::Error::with_description(&message, clap::ErrorKind::InvalidValue).exit();
+        }
+    }
+
+    // restrict output to syscalls referring to paths, relative to our working directory
+    // unless absolute, and to descriptors
+    for pattern in matches.values_of("path").into_iter().flatten() {
+        let pattern = if pattern.starts_with('/') {
+            pattern.to_string()
+        } else {
+            let cwd = env::current_dir().expect("unable to determine working directory");
+            format!("{}/{}", cwd.display(), pattern)
+        };
+        if let Err(e) = filter.add_path(&pattern) {
+            let message = format!("invalid path '{}': {}", pattern, e);
+            clap::Error::with_description(&message, clap::ErrorKind::InvalidValue).exit();
+        }
+    }
+    for fds in matches.values_of("fd").into_iter().flatten() {
+        if let Err(e) = filter.add_fds(fds) {
+            let message = format!("invalid descriptors '{}': {}", fds, e);
+            clap::Error::with_description(&message, clap::ErrorKind::InvalidValue).exit();
+        }
+    }
+
+    // initialize sink that events are streamed to, prefixing text output with the thread
+    // that caused each event if several are being traced into the same output
+    let follow = matches.is_present("follow");
+    let per_process = matches.occurrences_of("follow") > 1 && matches.is_present("output");
+    let json = matches.is_present("json");
+    let json_lines = matches.is_present("json_lines");
+    let options = TextOptions {
+        show_pid: follow || matches.occurrences_of("pid") > 1,
+        timestamps: match matches.occurrences_of("timestamps") {
+            0 => Timestamps::None,
+            1 => Timestamps::Seconds,
+            2 => Timestamps::Micros,
+            _ => Timestamps::Epoch,
+        },
+        relative: matches.is_present("relative"),
+        durations: matches.is_present("durations"),
+    };
+    let new_sink = move |out: Box<dyn io::Write>, options: TextOptions| -> Box<dyn EventSink> {
+        if json {
+            Box::new(JsonDocumentSink::new(out))
+        } else if json_lines {
+            Box::new(JsonLinesSink::new(out))
+        } else {
+            Box::new(TextSink::new(out, options))
+        }
+    };
+    let sink: Box<dyn EventSink> = match matches.value_of("output") {
+        Some(path) if per_process => {
+            let options = TextOptions { show_pid: false, ..options };
+            Box::new(PerProcessSink::new(path, Box::new(move |out| new_sink(out, options))))
+        },
+        Some(path) => match File::create(path) {
+            Ok(file) => new_sink(Box::new(file), options),
+            Err(e) => {
+                let _ = writeln!(io::stderr(), "jtrace: unable to create output file '{}': {}", path, e);
+                process::exit(1);
+            },
+        },
+        None => new_sink(Box::new(io::stdout()), options),
+    };
+
+    // summarize the trace at exit if requested, replacing the trace itself with -c
+    let sink: Box<dyn EventSink> = if matches.is_present("summary") {
+        Box::new(SummarySink::new(sink, false))
+    } else if matches.is_present("summary_trace") {
+        Box::new(SummarySink::new(sink, true))
+    } else {
+        sink
+    };
+
+    // initialize wrapper for interactions
+    let track_fds = matches.is_present("decode_fds") || filter.filters_paths();
+    let manager = SyscallManager::new(sink, filter, matches.value_of("syscall_header"), track_fds);
+    let decode = DecodeOptions {
+        string_limit,
+        hex: match matches.occurrences_of("hex") {
+            0 => HexStyle::Never,
+            1 => HexStyle::NonAscii,
+            _ => HexStyle::All,
+        },
+        dump_reads,
+        dump_writes,
+    };
+    let mut parent = Parent::new(manager, decode, follow, matches.is_present("regs"));
+
+    // attach to already running processes, detaching and leaving them running when interrupted
+    if let Some(pids) = matches.values_of("pid") {
+        for pid in pids {
+            let pid = pid.parse::<pid_t>().unwrap();
+
+            // release the processes attached so far, which are all stopped
+            if let Err(e) = parent.attach(pid) {
+                let _ = writeln!(io::stderr(), "jtrace: unable to attach to process {}: {}", pid, e);
+                let stopped: Vec<pid_t> = parent.tracees.keys().cloned().collect();
+                let _ = parent.detach_all(&stopped);
+                process::exit(1);
+            }
+        }
+
+        info!("Installing signal handlers to detach on interrupt");
+        let action = signal::SigAction::new(signal::SigHandler::Handler(handle_interrupt),
+                                            signal::SaFlags::empty(),
+                                            signal::SigSet::empty());
+        unsafe {
+            signal::sigaction(signal::Signal::SIGINT, &action).expect("unable to install SIGINT handler");
+            signal::sigaction(signal::Signal::SIGTERM, &action).expect("unable to install SIGTERM handler");
+        }
+
+        info!("Executing parent with tracing");
+        process::exit(run(&mut parent));
+    }
+
     // collect args into vec
     let args = matches.values_of("command")
                       .unwrap()
@@ -256,37 +1159,29 @@ fn main() {
     let result = unistd::fork().expect("unable to call fork(2)");
     match result {
         unistd::ForkResult::Parent { child } => {
-
-            // initialize wrapper for interactions
-            let flag = matches.is_present("json");
-            let mut pid = Parent::new(child.as_raw(), flag);
-
             info!("Tracing parent process");
 
-            // in parent, wait for process event from child
-            info!("Waiting for child process to send SIGSTOP");
-            if let Err(e) = pid.wait() {
+            // in parent, wait for process event from child and set trace options
+            if let Err(e) = parent.trace_child(child.as_raw()) {
                 panic!("Error: {:?}", e);
             }
 
-            // set trace options
-            info!("Setting trace options with PTRACE_SETOPTIONS");
-            helpers::set_options(child.as_raw(), options::PTRACE_O_TRACESYSGOOD.into());
-
-            // execute loop that examines through syscalls
+            // execute loop that examines through syscalls, exiting with the child's status
             info!("Executing parent with tracing");
-            pid.run();
+            process::exit(run(&mut parent));
         },
         unistd::ForkResult::Child => {
             info!("Tracing child process");
 
             // start tracing process, notifying parent through wait(2)
             info!("Child process executing PTRACE_TRACEME");
-            helpers::traceme();
+            if let Err(e) = helpers::traceme() {
+                panic!("failed to call ptrace(2) in child process: {:?}", e);
+            }
 
             // send a SIGSTOP in order to stop child process for parent introspection
             info!("Sending SIGTRAP, going back to parent process");
-            signal::kill(unistd::getpid(), signal::Signal::SIGSTOP);
+            signal::kill(unistd::getpid(), signal::Signal::SIGSTOP).expect("unable to stop child process");
 
             // execute child process with tracing until termination
             info!("Executing rest of child execution until termination");
@@ -294,7 +1189,8 @@ fn main() {
             let c_args: Vec<CString> = args.iter()
                 .map(|&arg| CString::new(arg).expect("CString::new() failed"))
                 .collect();
-            unistd::execvp(&c_cmd, &c_args).ok().expect("failed to call execvp(2) in child process");
+            let Err(e) = unistd::execvp(&c_cmd, &c_args);
+            panic!("failed to call execvp(2) in child process: {:?}", e);
         }
     }
 }
@@ -5,12 +5,12 @@
 //!     This is a re-implementation of `ptrace(2)` that allows safer
 //!     usage through a specialized helper function.
 
-#![allow(dead_code)]
 pub mod consts {
 
     /// these represent `PtraceRequest`s a tracer
     /// can send to tracee in order to perform actions
     /// on attached process.
+    #[allow(dead_code)]
     pub mod requests {
         use libc::c_int;
 
@@ -48,74 +48,80 @@ pub mod consts {
         pub const PTRACE_INTERRUPT:   PtraceRequest = 0x4207;
         pub const PTRACE_LISTEN:      PtraceRequest = 0x4208;
         pub const PTRACE_PEEKSIGINFO: PtraceRequest = 0x4209;
+        pub const PTRACE_GET_SYSCALL_INFO: PtraceRequest = 0x420e;
     }
 
     pub mod options {
         use libc::c_int;
 
-        /// represents constants to be used for the `data`
-        /// parameter when calling ptrace with PTRACE_SETOPTIONS
-        type PtraceOption = c_int;
-
-		pub const PTRACE_O_TRACESYSGOOD:   PtraceOption = 0x01;
-        pub const PTRACE_O_TRACEFORK:	   PtraceOption = 0x02;
-        pub const PTRACE_O_TRACEVFORK:	   PtraceOption = 0x03;
-        pub const PTRACE_O_TRACECLONE:	   PtraceOption = 0x04;
-        pub const PTRACE_O_TRACEEXEC:	   PtraceOption = 0x05;
-        pub const PTRACE_O_TRACEVFORKDONE: PtraceOption = 0x06;
-        pub const PTRACE_O_TRACEEXIT: 	   PtraceOption = 0x07;
-		pub const PTRACE_O_TRACESECCOMP:   PtraceOption = 0x08;
-    }
-
-
-	pub mod regs {
-
-		/// i64 represents value register value
-		type RegVal = i64;
-
-        pub const R15:		   RegVal = 0 * 8;
-        pub const R14:		   RegVal = 1 * 8;
-        pub const R13:		   RegVal = 2 * 8;
-        pub const R12:		   RegVal = 3 * 8;
-        pub const RBP:		   RegVal = 4 * 8;
-        pub const RBX:		   RegVal = 5 * 8;
-        pub const R11:		   RegVal = 6 * 8;
-        pub const R10:		   RegVal = 7 * 8;
-        pub const R9:		   RegVal = 8 * 8;
-        pub const R8:		   RegVal = 9 * 8;
-        pub const RAX:		   RegVal = 10 * 8;
-        pub const RCX:		   RegVal = 11 * 8;
-        pub const RDX:		   RegVal = 12 * 8;
-        pub const RSI:		   RegVal = 13 * 8;
-        pub const RDI:		   RegVal = 14 * 8;
-        pub const ORIG_RAX:    RegVal = 15 * 8;
-        pub const RIP: 		   u64    = 16 * 8;
-        pub const CS: 		   RegVal = 17 * 8;
-        pub const EFLAGS: 	   RegVal = 18 * 8;
-        pub const RSP: 		   RegVal = 19 * 8;
-        pub const SS: 		   RegVal = 20 * 8;
-        pub const FS_BASE: 	   RegVal = 21 * 8;
-        pub const GS_BASE: 	   RegVal = 22 * 8;
-        pub const DS:		   RegVal = 23 * 8;
-        pub const ES:		   RegVal = 24 * 8;
-        pub const FS:		   RegVal = 25 * 8;
-		pub const GS:		   RegVal = 26 * 8;
+        bitflags! {
+            /// represents flags to be used for the `data`
+            /// parameter when calling ptrace with PTRACE_SETOPTIONS
+            /// or PTRACE_SEIZE. These are bit flags, and can be combined.
+            pub struct PtraceOptions: c_int {
+                const TRACESYSGOOD    = 1 << 0;
+                const TRACEFORK       = 1 << 1;
+                const TRACEVFORK      = 1 << 2;
+                const TRACECLONE      = 1 << 3;
+                const TRACEEXEC       = 1 << 4;
+                const TRACEVFORKDONE  = 1 << 5;
+                const TRACEEXIT       = 1 << 6;
+                const TRACESECCOMP    = 1 << 7;
+                const EXITKILL        = 1 << 20;
+                const SUSPEND_SECCOMP = 1 << 21;
+            }
+        }
+    }
+
+
+    /// represents the events reported in the upper bits of a
+    /// wait status when a tracee stops due to a traced event,
+    /// enabled through the corresponding `PtraceOptions`
+    #[allow(dead_code)]
+    pub mod events {
+        use libc::c_int;
+
+        type PtraceEvent = c_int;
+
+        pub const PTRACE_EVENT_FORK:       PtraceEvent = 1;
+        pub const PTRACE_EVENT_VFORK:      PtraceEvent = 2;
+        pub const PTRACE_EVENT_CLONE:      PtraceEvent = 3;
+        pub const PTRACE_EVENT_EXEC:       PtraceEvent = 4;
+        pub const PTRACE_EVENT_VFORK_DONE: PtraceEvent = 5;
+        pub const PTRACE_EVENT_EXIT:       PtraceEvent = 6;
+        pub const PTRACE_EVENT_SECCOMP:    PtraceEvent = 7;
+        pub const PTRACE_EVENT_STOP:       PtraceEvent = 128;
+    }
+
+
+    /// represents the types of register sets that can be retrieved
+    /// with PTRACE_GETREGSET, as defined for ELF core dump notes
+    #[allow(dead_code)]
+    pub mod regsets {
+        use libc::c_int;
+
+        type RegSet = c_int;
+
+        pub const NT_PRSTATUS:             RegSet = 1;
+        pub const NT_PRFPREG:              RegSet = 2;
+        pub const NT_ARM_SYSTEM_CALL:      RegSet = 0x404;
     }
 }
 
 
+#[allow(clippy::module_inception)]
 mod ptrace {
     use libc::{c_int, c_long, c_void, pid_t};
     use nix::errno::Errno;
 
-    /// defines an `unsafe` foreign function interface to the `ptrace(2)` system call.
-    /// `ptrace(2)`'s original C function definition is as follows:
-    ///
-    /// ```
-    ///     long ptrace(enum __ptrace_request request, pid_t pid,
-    ///                 void *addr, void *data);
-    /// ```
-    extern {
+    extern "C" {
+        /// defines an `unsafe` foreign function interface to the `ptrace(2)` system call.
+        /// `ptrace(2)`'s original C function definition is as follows:
+        ///
+        /// ```text
+        ///     long ptrace(enum __ptrace_request request, pid_t pid,
+        ///                 void *addr, void *data);
+        /// ```
         fn ptrace(request: c_int, pid: pid_t,
                   addr: * const c_void, data: * const c_void) -> c_long;
     }
@@ -123,7 +129,7 @@ mod ptrace {
 
     /// `exec_ptrace()` is the main and safest interface for calling the unsafe `ptrace` FFI.
     /// It does error-checking to ensure that the user receives errors through Result<T>, and
-    pub fn exec_ptrace(request: c_int, pid: pid_t, addr: *mut c_void, data: *mut c_void) -> Result<i64, Errno> {
+    pub fn exec_ptrace(request: c_int, pid: pid_t, addr: *mut c_void, data: *mut c_void) -> Result<c_long, Errno> {
         use ptrace::consts::requests;
 
         // on PTRACE_PEEK* commands, a successful request might still return -1. As a result,
@@ -146,9 +152,11 @@ mod ptrace {
             _ => {},
         }
 
-        // for other conventional PTRACE_* commands
+        // for other conventional PTRACE_* commands, of which PTRACE_GET_SYSCALL_INFO
+        // returns the size of the data available
         match unsafe { ptrace(request, pid, addr, data) } {
             -1 => Err(Errno::last()),
+            ret if request == requests::PTRACE_GET_SYSCALL_INFO => Ok(ret),
             _ => Ok(0)
         }
     }
@@ -160,10 +168,11 @@ mod ptrace {
 /// in order to perform process debugging.
 pub mod helpers {
     use std::{ptr, mem};
-    use std::io::{Error, ErrorKind};
-    use libc::pid_t;
+    use std::io::Error;
+    use libc::{c_long, pid_t};
 
     use ptrace::{ptrace, consts};
+    use ptrace::consts::options::PtraceOptions;
 
 
     /// alias the pid_t for better clarification
@@ -178,7 +187,7 @@ pub mod helpers {
     /// a fork call actually spawned off a child process.
     pub fn traceme() -> Result<(), Error> {
         if let Err(e) = ptrace::exec_ptrace(consts::requests::PTRACE_TRACEME, 0, NULL, NULL) {
-            let err = Error::new(ErrorKind::Other, e.desc());
+            let err = Error::other(e.desc());
             return Err(err);
         }
         Ok(())
@@ -187,10 +196,11 @@ pub mod helpers {
 
     /// `syscall()` call with error-checking. PTRACE_SYSCALL is used when tracer steps through
     /// syscall entry/exit in trace, and enables debugging process to perform further introspection.
-    pub fn syscall(pid: InferiorType) -> Result<i64, Error> {
-        match ptrace::exec_ptrace(consts::requests::PTRACE_SYSCALL, pid, NULL, NULL) {
+    /// If `signal` is non-zero, it is injected into the tracee as it is resumed.
+    pub fn syscall(pid: InferiorType, signal: i64) -> Result<c_long, Error> {
+        match ptrace::exec_ptrace(consts::requests::PTRACE_SYSCALL, pid, NULL, signal as *mut libc::c_void) {
             Err(e) => {
-                let err = Error::new(ErrorKind::Other, e.desc());
+                let err = Error::other(e.desc());
                 Err(err)
             },
             Ok(res) => Ok(res)
@@ -198,46 +208,381 @@ pub mod helpers {
     }
 
 
-	/// `peek_user()` call with error-checking. PTRACE_PEEKUSER is used in order to
-	/// introspect register values when encountering SYSCALL_ENTER or SYSCALL_EXIT.
-	pub fn peek_user(pid: InferiorType, register: i64) -> Result<i64, Error> {
-        match ptrace::exec_ptrace(consts::requests::PTRACE_PEEKUSER, pid, register as *mut libc::c_void, NULL){
+    /// `peek_data()` call with error-checking. PTRACE_PEEKDATA is used in order to
+    /// read a single word from the tracee's address space, which is as wide as a long.
+    pub fn peek_data(pid: InferiorType, addr: u64) -> Result<c_long, Error> {
+        match ptrace::exec_ptrace(consts::requests::PTRACE_PEEKDATA, pid, addr as *mut libc::c_void, NULL) {
             Err(e) => {
-                let err = Error::new(ErrorKind::Other, e.desc());
+                let err = Error::other(e.desc());
                 Err(err)
             },
             Ok(res) => Ok(res)
         }
-	}
+    }
+
+
+    /// `read_memory()` reads `len` bytes from the tracee's address space a word at a time
+    /// with PTRACE_PEEKDATA. Words are read from aligned addresses, which never straddle
+    /// a page boundary, such that bytes at the end of a mapping can be read even if the
+    /// memory that follows is not mapped. Ranges running past the end of the address
+    /// space are reported as an error rather than wrapping around.
+    pub fn read_memory(pid: InferiorType, addr: u64, len: usize) -> Result<Vec<u8>, Error> {
+        let word_size = mem::size_of::<c_long>() as u64;
+        let skip = (addr % word_size) as usize;
+        let end = skip.checked_add(len)
+                      .filter(|_| addr.checked_add(len as u64).is_some())
+                      .ok_or_else(out_of_range)?;
+        let mut bytes: Vec<u8> = Vec::with_capacity(end);
+
+        let mut word_addr = addr - skip as u64;
+        while bytes.len() < end {
+            bytes.extend_from_slice(&peek_data(pid, word_addr)?.to_ne_bytes());
+            word_addr = word_addr.checked_add(word_size).ok_or_else(out_of_range)?;
+        }
+        bytes.drain(..skip);
+        bytes.truncate(len);
+        Ok(bytes)
+    }
+
+
+    /// `read_string()` reads a NUL-terminated string from the tracee's address space a
+    /// word at a time with PTRACE_PEEKDATA, stopping after `limit` bytes. As with
+    /// `read_memory()`, words are read from aligned addresses. The returned flag is set
+    /// if the string continues past `limit` and was therefore truncated.
+    pub fn read_string(pid: InferiorType, addr: u64, limit: usize) -> Result<(Vec<u8>, bool), Error> {
+        let word_size = mem::size_of::<c_long>() as u64;
+        let mut bytes: Vec<u8> = Vec::new();
+        let mut skip = (addr % word_size) as usize;
+        let mut word_addr = addr - skip as u64;
+
+        loop {
+            let word = peek_data(pid, word_addr)?;
+            for byte in word.to_ne_bytes().iter().skip(skip) {
+                if *byte == 0 {
+                    return Ok((bytes, false));
+                }
+
+                // a non-NUL byte past the limit means the string was truncated
+                if bytes.len() == limit {
+                    return Ok((bytes, true));
+                }
+                bytes.push(*byte);
+            }
+            skip = 0;
+            word_addr = word_addr.checked_add(word_size).ok_or_else(out_of_range)?;
+        }
+    }
+
+
+    /// `out_of_range()` reports tracee memory that runs past the end of the address space.
+    fn out_of_range() -> Error {
+        Error::from_raw_os_error(libc::EFAULT)
+    }
+
+
+    /// `get_regset()` call with error-checking. PTRACE_GETREGSET is used in order to
+    /// retrieve a register set in the layout of the tracee's architecture, which unlike
+    /// PTRACE_GETREGS is supported everywhere. The kernel reports how much of the buffer
+    /// it filled, so the returned bytes are sized to the register set.
+    pub fn get_regset(pid: InferiorType, regset: libc::c_int) -> Result<Vec<u8>, Error> {
 
+        // large enough for the general-purpose registers of any architecture
+        let mut buf = [0u64; 64];
+        let mut iov = libc::iovec {
+            iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+            iov_len: mem::size_of_val(&buf),
+        };
 
-    /// `get_regs()` call with error-checking. PTRACE_GETREGS is used in order to
-    /// get and store the currently set register state. The wrapper actually returns this back to
-    /// the developer in a struct.
+        let data = &mut iov as *mut libc::iovec as *mut libc::c_void;
+        if let Err(e) = ptrace::exec_ptrace(consts::requests::PTRACE_GETREGSET, pid, regset as *mut libc::c_void, data) {
+            let err = Error::other(e.desc());
+            return Err(err);
+        }
+
+        let bytes: Vec<u8> = buf.iter().flat_map(|word| word.to_ne_bytes().to_vec()).collect();
+        Ok(bytes[..iov.iov_len].to_vec())
+    }
+
+
+    /// `get_regs()` call with error-checking. PTRACE_GETREGSET is used in order to get
+    /// and store the general-purpose registers in bulk as a `user_regs_struct`, which is
+    /// laid out for the architecture jtrace was built for. Tracees of another layout,
+    /// such as i386 tasks traced from x86_64, are reported as an error, as the kernel
+    /// only fills in part of the struct for them; `get_regset()` covers those.
+    #[allow(dead_code)]
     pub fn get_regs(pid: InferiorType) -> Result<libc::user_regs_struct, Error> {
         unsafe {
 
-            // initialize uninitialized memory for register struct
-            let regs: libc::user_regs_struct = mem::uninitialized();
-
-            // cast user_regs_struct to c_void using mem::transmute_copy
-            if let Err(e) = ptrace::exec_ptrace(consts::requests::PTRACE_GETREGS, pid, NULL,
-                                                mem::transmute_copy::<libc::user_regs_struct, *mut libc::c_void>(&regs)) {
-                let err = Error::new(ErrorKind::Other, e.desc());
+            // initialize zeroed memory for register struct, and describe it to the kernel
+            let mut regs: libc::user_regs_struct = mem::zeroed();
+            let mut iov = libc::iovec {
+                iov_base: &mut regs as *mut libc::user_regs_struct as *mut libc::c_void,
+                iov_len: mem::size_of::<libc::user_regs_struct>(),
+            };
+
+            let regset = consts::regsets::NT_PRSTATUS as *mut libc::c_void;
+            let data = &mut iov as *mut libc::iovec as *mut libc::c_void;
+            if let Err(e) = ptrace::exec_ptrace(consts::requests::PTRACE_GETREGSET, pid, regset, data) {
+                let err = Error::other(e.desc());
                 return Err(err);
             }
+            if iov.iov_len != mem::size_of::<libc::user_regs_struct>() {
+                return Err(Error::other("register set not laid out as user_regs_struct"));
+            }
             Ok(regs)
         }
     }
 
 
+    /// `set_regs()` call with error-checking. PTRACE_SETREGSET is used in order to
+    /// overwrite the general-purpose registers of the tracee in bulk, such as with a
+    /// struct previously retrieved with `get_regs()` and modified.
+    #[allow(dead_code)]
+    pub fn set_regs(pid: InferiorType, regs: &libc::user_regs_struct) -> Result<(), Error> {
+        let mut iov = libc::iovec {
+            iov_base: regs as *const libc::user_regs_struct as *mut libc::c_void,
+            iov_len: mem::size_of::<libc::user_regs_struct>(),
+        };
+
+        let regset = consts::regsets::NT_PRSTATUS as *mut libc::c_void;
+        let data = &mut iov as *mut libc::iovec as *mut libc::c_void;
+        if let Err(e) = ptrace::exec_ptrace(consts::requests::PTRACE_SETREGSET, pid, regset, data) {
+            let err = Error::other(e.desc());
+            return Err(err);
+        }
+        Ok(())
+    }
+
+
+    /// `get_syscall_info()` call with error-checking. PTRACE_GET_SYSCALL_INFO is used to
+    /// retrieve a `struct ptrace_syscall_info` describing the syscall-stop of a tracee, of
+    /// which the kernel fills in as much as applies to the stop. As with `get_siginfo()`,
+    /// the raw OS error is preserved, as EIO denotes a kernel older than 5.3.
+    pub fn get_syscall_info(pid: InferiorType) -> Result<Vec<u8>, Error> {
+        let mut buf = [0u8; 88];
+        let data = buf.as_mut_ptr() as *mut libc::c_void;
+        let size = buf.len() as *mut libc::c_void;
+        match ptrace::exec_ptrace(consts::requests::PTRACE_GET_SYSCALL_INFO, pid, size, data) {
+            Err(e) => Err(Error::from_raw_os_error(e as i32)),
+            Ok(len) => Ok(buf[..(len as usize).min(buf.len())].to_vec()),
+        }
+    }
+
+
+    /// `seize()` call with error-checking. PTRACE_SEIZE is used to begin tracing an
+    /// already running process without stopping it, setting trace options at the same time.
+    pub fn seize(pid: InferiorType, options: PtraceOptions) -> Result<(), Error> {
+        let data = options.bits() as *mut libc::c_void;
+        if let Err(e) = ptrace::exec_ptrace(consts::requests::PTRACE_SEIZE, pid, NULL, data) {
+            let err = Error::other(e.desc());
+            return Err(err);
+        }
+        Ok(())
+    }
+
+
+    /// `interrupt()` call with error-checking. PTRACE_INTERRUPT is used to stop a
+    /// tracee that was attached with PTRACE_SEIZE.
+    pub fn interrupt(pid: InferiorType) -> Result<(), Error> {
+        if let Err(e) = ptrace::exec_ptrace(consts::requests::PTRACE_INTERRUPT, pid, NULL, NULL) {
+            let err = Error::other(e.desc());
+            return Err(err);
+        }
+        Ok(())
+    }
+
+
+    /// `detach()` call with error-checking. PTRACE_DETACH is used to stop tracing a
+    /// stopped tracee and resume it, delivering `signal` if it is non-zero.
+    pub fn detach(pid: InferiorType, signal: i64) -> Result<(), Error> {
+        if let Err(e) = ptrace::exec_ptrace(consts::requests::PTRACE_DETACH, pid, NULL, signal as *mut libc::c_void) {
+            let err = Error::other(e.desc());
+            return Err(err);
+        }
+        Ok(())
+    }
+
+
+    /// `get_event_msg()` call with error-checking. PTRACE_GETEVENTMSG is used to retrieve
+    /// the message for the ptrace event just reported, such as the PID of a new child.
+    pub fn get_event_msg(pid: InferiorType) -> Result<u64, Error> {
+        let mut msg: libc::c_ulong = 0;
+        let data = &mut msg as *mut libc::c_ulong as *mut libc::c_void;
+        if let Err(e) = ptrace::exec_ptrace(consts::requests::PTRACE_GETEVENTMSG, pid, NULL, data) {
+            let err = Error::other(e.desc());
+            return Err(err);
+        }
+        Ok(msg as u64)
+    }
+
+
+    /// `get_siginfo()` call with error-checking. PTRACE_GETSIGINFO is used to retrieve
+    /// information about the signal that caused the tracee to stop. Unlike other helpers,
+    /// the raw OS error is preserved, as EINVAL denotes a group-stop rather than a signal.
+    pub fn get_siginfo(pid: InferiorType) -> Result<libc::siginfo_t, Error> {
+        unsafe {
+            let mut info: libc::siginfo_t = mem::zeroed();
+            let data = &mut info as *mut libc::siginfo_t as *mut libc::c_void;
+            if let Err(e) = ptrace::exec_ptrace(consts::requests::PTRACE_GETSIGINFO, pid, NULL, data) {
+                return Err(Error::from_raw_os_error(e as i32));
+            }
+            Ok(info)
+        }
+    }
+
+
+    /// `listen()` call with error-checking. PTRACE_LISTEN is used to let a seized tracee
+    /// remain in a group-stop, while still reporting when it is continued or signalled.
+    pub fn listen(pid: InferiorType) -> Result<(), Error> {
+        if let Err(e) = ptrace::exec_ptrace(consts::requests::PTRACE_LISTEN, pid, NULL, NULL) {
+            let err = Error::other(e.desc());
+            return Err(err);
+        }
+        Ok(())
+    }
+
+
     /// `set_options()` called with error-checking. PTRACE_SETOPTIONS is called,
     /// with flag options set by users.
-    pub fn set_options(pid: InferiorType, options: i64) -> Result<(), Error> {
-        if let Err(e) = ptrace::exec_ptrace(consts::requests::PTRACE_SETOPTIONS, pid, NULL, options as *mut libc::c_void) {
-            let err = Error::new(ErrorKind::Other, e.desc());
+    pub fn set_options(pid: InferiorType, options: PtraceOptions) -> Result<(), Error> {
+        let data = options.bits() as *mut libc::c_void;
+        if let Err(e) = ptrace::exec_ptrace(consts::requests::PTRACE_SETOPTIONS, pid, NULL, data) {
+            let err = Error::other(e.desc());
             return Err(err);
         }
         Ok(())
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use std::{mem, ptr, slice};
+    use libc::{self, pid_t};
+
+    use super::helpers;
+
+
+    /// `page_end_child()` maps a page followed by an unmapped one, places `bytes` at the
+    /// very end of the first page, and forks a child that stops itself under ptrace with
+    /// the same mapping. Returns the child and the address of the bytes.
+    fn page_end_child(bytes: &[u8]) -> (pid_t, u64) {
+        unsafe {
+            let page = libc::sysconf(libc::_SC_PAGESIZE) as usize;
+            let map = libc::mmap(ptr::null_mut(), page * 2, libc::PROT_READ | libc::PROT_WRITE,
+                                 libc::MAP_PRIVATE | libc::MAP_ANONYMOUS, -1, 0);
+            assert_ne!(map, libc::MAP_FAILED);
+            assert_eq!(libc::munmap((map as usize + page) as *mut libc::c_void, page), 0);
+
+            let addr = map as usize + page - bytes.len();
+            ptr::copy_nonoverlapping(bytes.as_ptr(), addr as *mut u8, bytes.len());
+
+            let child = libc::fork();
+            if child == 0 {
+                if helpers::traceme().is_ok() {
+                    libc::raise(libc::SIGSTOP);
+                }
+                libc::_exit(0);
+            }
+            let mut status = 0;
+            assert_eq!(libc::waitpid(child, &mut status, 0), child);
+            assert!(libc::WIFSTOPPED(status));
+            (child, addr as u64)
+        }
+    }
+
+
+    fn kill(child: pid_t) {
+        unsafe {
+            libc::kill(child, libc::SIGKILL);
+            libc::waitpid(child, ptr::null_mut(), 0);
+        }
+    }
+
+
+    #[test]
+    fn read_string_at_page_end() {
+        let (child, addr) = page_end_child(b"hello\0");
+        let result = helpers::read_string(child, addr, 32);
+        kill(child);
+        assert_eq!(result.unwrap(), (b"hello".to_vec(), false));
+    }
+
+
+    #[test]
+    fn read_string_truncated() {
+        let (child, addr) = page_end_child(b"/etc/hostname\0");
+        let result = helpers::read_string(child, addr, 4);
+        kill(child);
+        assert_eq!(result.unwrap(), (b"/etc".to_vec(), true));
+    }
+
+
+    #[test]
+    fn read_memory_at_page_end() {
+        let (child, addr) = page_end_child(b"abcdefghijk");
+        let whole = helpers::read_memory(child, addr, 11);
+        let tail = helpers::read_memory(child, addr + 9, 2);
+        let empty = helpers::read_memory(child, addr, 0);
+        kill(child);
+        assert_eq!(whole.unwrap(), b"abcdefghijk".to_vec());
+        assert_eq!(tail.unwrap(), b"jk".to_vec());
+        assert_eq!(empty.unwrap(), Vec::<u8>::new());
+    }
+
+
+    #[test]
+    fn read_memory_past_address_space() {
+        let (child, addr) = page_end_child(b"abc");
+        let huge = helpers::read_memory(child, addr, usize::MAX);
+        let wrapped = helpers::read_memory(child, u64::MAX - 2, 8);
+        let string = helpers::read_string(child, u64::MAX - 2, 8);
+        kill(child);
+        assert_eq!(huge.unwrap_err().raw_os_error(), Some(libc::EFAULT));
+        assert_eq!(wrapped.unwrap_err().raw_os_error(), Some(libc::EFAULT));
+        assert!(string.is_err());
+    }
+
+
+    /// `scratch()` returns a callee-saved register, which the stopped child does not
+    /// rely on until it is killed.
+    #[cfg(target_arch = "x86_64")]
+    fn scratch(regs: &mut libc::user_regs_struct) -> &mut u64 {
+        &mut regs.r12
+    }
+
+    #[cfg(target_arch = "x86")]
+    fn scratch(regs: &mut libc::user_regs_struct) -> &mut libc::c_long {
+        &mut regs.ebx
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    fn scratch(regs: &mut libc::user_regs_struct) -> &mut u64 {
+        &mut regs.regs[19]
+    }
+
+    #[cfg(target_arch = "riscv64")]
+    fn scratch(regs: &mut libc::user_regs_struct) -> &mut libc::c_ulong {
+        &mut regs.s1
+    }
+
+
+    #[test]
+    fn regs_round_trip() {
+        let (child, _) = page_end_child(b"\0");
+        let result = helpers::get_regs(child).and_then(|mut regs| {
+            *scratch(&mut regs) = 0x1234_5678;
+            helpers::set_regs(child, &regs)?;
+            Ok((regs, helpers::get_regs(child)?))
+        });
+        kill(child);
+
+        // the register was written, and every other register was left as it was
+        let (set, mut got) = result.unwrap();
+        assert_eq!(*scratch(&mut got), 0x1234_5678);
+        let bytes = |regs: &libc::user_regs_struct| unsafe {
+            slice::from_raw_parts(regs as *const _ as *const u8, mem::size_of::<libc::user_regs_struct>()).to_vec()
+        };
+        assert_eq!(bytes(&got), bytes(&set));
+    }
+}
@@ -0,0 +1,324 @@
+//! sink.rs
+//!
+//!     Defines the sinks that traced events are written to.
+//!
+//!     Events are written out as soon as they are complete
+//!     rather than buffered until the tracee exits, such that
+//!     traces of long-running processes can be followed live.
+
+use std::io::{self, Write};
+use std::mem;
+use std::fs::File;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+use serde_json;
+
+use event::Event;
+use summary::Aggregator;
+
+// column at which the return value of a syscall is aligned, as in strace
+static RESULT_COLUMN: usize = 40;
+
+
+/// An `EventSink` receives every completed event in the order it happened.
+pub trait EventSink {
+
+    /// `write_event()` outputs a single event.
+    fn write_event(&mut self, event: Event) -> io::Result<()>;
+
+    /// `finish()` is called once tracing is done, in order to complete the output.
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+
+/// How the time of each event is printed in text output.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Timestamps {
+    None,
+
+    /// local time of day, as with `-t`
+    Seconds,
+
+    /// local time of day with microseconds, as with `-tt`
+    Micros,
+
+    /// seconds since the epoch with microseconds, as with `-ttt`
+    Epoch,
+}
+
+
+/// Controls what is printed alongside each event in text output.
+#[derive(Clone, Copy)]
+pub struct TextOptions {
+
+    /// prefix each line with the thread that caused it
+    pub show_pid: bool,
+
+    /// prefix each line with the time it occurred at
+    pub timestamps: Timestamps,
+
+    /// prefix each line with the time elapsed since the previous one
+    pub relative: bool,
+
+    /// suffix each syscall with the time spent in it
+    pub durations: bool,
+}
+
+
+/// Writes each event as a line of trace in the same layout as strace, prefixed with
+/// the thread that caused it when several are being traced, such that existing
+/// tooling for strace logs can consume it.
+pub struct TextSink {
+    out: Box<dyn Write>,
+    options: TextOptions,
+    last_ns: Option<u64>,
+}
+
+
+impl TextSink {
+
+    pub fn new(out: Box<dyn Write>, options: TextOptions) -> Self {
+        Self { out, options, last_ns: None }
+    }
+
+
+    /// `prefix()` renders the thread and times printed before an event.
+    fn prefix(&mut self, event: &Event) -> String {
+        let mut prefix = match event.tid() {
+            Some(tid) if self.options.show_pid => format!("[pid {:>5}] ", tid),
+            _ => String::new(),
+        };
+
+        let timestamp_ns = match event.timestamp_ns() {
+            Some(timestamp_ns) => timestamp_ns,
+            None => return prefix,
+        };
+        let (secs, micros) = (timestamp_ns / 1_000_000_000, timestamp_ns % 1_000_000_000 / 1000);
+        match self.options.timestamps {
+            Timestamps::None => {},
+            Timestamps::Seconds => prefix.push_str(&format!("{} ", local_time(secs))),
+            Timestamps::Micros => prefix.push_str(&format!("{}.{:06} ", local_time(secs), micros)),
+            Timestamps::Epoch => prefix.push_str(&format!("{}.{:06} ", secs, micros)),
+        }
+
+        if self.options.relative {
+            let elapsed = timestamp_ns.saturating_sub(self.last_ns.unwrap_or(timestamp_ns));
+            prefix.push_str(&format!("{:>6}.{:06} ", elapsed / 1_000_000_000, elapsed % 1_000_000_000 / 1000));
+        }
+        self.last_ns = Some(timestamp_ns);
+        prefix
+    }
+}
+
+
+/// `local_time()` renders seconds since the epoch as the local time of day.
+fn local_time(secs: u64) -> String {
+    let time = secs as libc::time_t;
+    let mut tm: libc::tm = unsafe { mem::zeroed() };
+    unsafe {
+        libc::localtime_r(&time, &mut tm);
+    }
+    format!("{:02}:{:02}:{:02}", tm.tm_hour, tm.tm_min, tm.tm_sec)
+}
+
+
+impl EventSink for TextSink {
+
+    fn write_event(&mut self, event: Event) -> io::Result<()> {
+        let mut line = self.prefix(&event);
+
+        // pad syscalls such that their return values line up
+        match event {
+            Event::Syscall(ref syscall) => {
+                line.push_str(&syscall.call());
+                let width = RESULT_COLUMN.max(line.len() + 1);
+                line = format!("{:<width$}= {}", line, syscall.result(), width = width);
+                if let (true, Some(duration)) = (self.options.durations, syscall.duration()) {
+                    line.push_str(&format!(" <{}.{:06}>", duration.as_secs(), duration.subsec_micros()));
+                }
+            },
+            _ => line.push_str(&event.to_string()),
+        }
+        writeln!(self.out, "{}", line)?;
+
+        // follow syscalls with hexdumps of any data dumped in full
+        if let Event::Syscall(ref syscall) = event {
+            for dump in syscall.hexdumps() {
+                writeln!(self.out, "{}", dump)?;
+            }
+        }
+        self.out.flush()
+    }
+}
+
+
+/// Writes each event as a JSON object on its own line (JSON Lines).
+pub struct JsonLinesSink {
+    out: Box<dyn Write>,
+}
+
+
+impl JsonLinesSink {
+
+    pub fn new(out: Box<dyn Write>) -> Self {
+        Self { out }
+    }
+}
+
+
+impl EventSink for JsonLinesSink {
+
+    fn write_event(&mut self, event: Event) -> io::Result<()> {
+        serde_json::to_writer(&mut self.out, &event)?;
+        writeln!(self.out)?;
+        self.out.flush()
+    }
+}
+
+
+/// Writes the trace as a single JSON document of the form `{"syscalls": [...], "events": [...]}`,
+/// where `syscalls` holds every syscall as an object, and `events` every other event, such as
+/// signals and exits, tagged as in JSON Lines. Syscalls are written out as they complete, while
+/// other events are held back until tracing is done, so the document is only valid once finished.
+pub struct JsonDocumentSink {
+    out: Box<dyn Write>,
+    count: usize,
+    events: Vec<Event>,
+}
+
+
+impl JsonDocumentSink {
+
+    pub fn new(out: Box<dyn Write>) -> Self {
+        Self { out, count: 0, events: Vec::new() }
+    }
+}
+
+
+impl EventSink for JsonDocumentSink {
+
+    fn write_event(&mut self, event: Event) -> io::Result<()> {
+        let syscall = match event {
+            Event::Syscall(syscall) => syscall,
+            event => {
+                self.events.push(event);
+                return Ok(());
+            },
+        };
+        if self.count == 0 {
+            write!(self.out, "{{\"syscalls\":[")?;
+        } else {
+            write!(self.out, ",")?;
+        }
+        serde_json::to_writer(&mut self.out, &syscall)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        if self.count == 0 {
+            write!(self.out, "{{\"syscalls\":[")?;
+        }
+        write!(self.out, "],\"events\":")?;
+        serde_json::to_writer(&mut self.out, &self.events)?;
+        writeln!(self.out, "}}")?;
+        self.out.flush()
+    }
+}
+
+
+/// Builds the sink that the events of a single process are written to.
+pub type SinkFactory = Box<dyn Fn(Box<dyn Write>) -> Box<dyn EventSink>>;
+
+
+/// Writes the events of each traced process to a separate file named `PREFIX.<pid>`,
+/// each in the format of the sink built by the given factory. Files are created
+/// as soon as the first event of a process is seen, while events that belong to
+/// no process, such as the summary, are written to `PREFIX` itself.
+pub struct PerProcessSink {
+    prefix: String,
+    factory: SinkFactory,
+    sinks: HashMap<Option<i32>, Box<dyn EventSink>>,
+}
+
+
+impl PerProcessSink {
+
+    pub fn new(prefix: &str, factory: SinkFactory) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            factory,
+            sinks: HashMap::new(),
+        }
+    }
+}
+
+
+impl EventSink for PerProcessSink {
+
+    fn write_event(&mut self, event: Event) -> io::Result<()> {
+        let sink = match self.sinks.entry(event.pid()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let path = match *entry.key() {
+                    Some(pid) => format!("{}.{}", self.prefix, pid),
+                    None => self.prefix.clone(),
+                };
+                let file = File::create(path)?;
+                entry.insert((self.factory)(Box::new(file)))
+            }
+        };
+        sink.write_event(event)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        for sink in self.sinks.values_mut() {
+            sink.finish()?;
+        }
+        Ok(())
+    }
+}
+
+
+/// Aggregates syscalls into a summary which is written to the wrapped sink once
+/// tracing is done. Events are also passed through to it if `trace` is set, such
+/// that the summary follows the full trace.
+pub struct SummarySink {
+    inner: Box<dyn EventSink>,
+    trace: bool,
+    aggregator: Aggregator,
+}
+
+
+impl SummarySink {
+
+    pub fn new(inner: Box<dyn EventSink>, trace: bool) -> Self {
+        Self {
+            inner,
+            trace,
+            aggregator: Aggregator::default(),
+        }
+    }
+}
+
+
+impl EventSink for SummarySink {
+
+    fn write_event(&mut self, event: Event) -> io::Result<()> {
+        if let Event::Syscall(ref syscall) = event {
+            self.aggregator.record(syscall);
+        }
+        if self.trace {
+            self.inner.write_event(event)?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.inner.write_event(Event::Summary(self.aggregator.summary()))?;
+        self.inner.finish()
+    }
+}
@@ -0,0 +1,689 @@
+//! structs.rs
+//!
+//!     Decodes structures that syscall arguments point to, such
+//!     as `struct stat` or `struct sockaddr`, from tracee memory
+//!     into values that are rendered as strace does in text, e.g.
+//!     `{st_mode=S_IFREG|0644, st_size=1234, ...}`, and as nested
+//!     objects in JSON.
+//!
+//!     Layouts differ between architectures, so decoding takes
+//!     the architecture the syscall was made through, and is done
+//!     from raw bytes independently of the host.
+
+use std::fmt;
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use serde::{Serialize, Serializer};
+use serde::ser::{SerializeMap, SerializeSeq};
+use libc::pid_t;
+
+use arch::Arch;
+use args::{self, ArgType, Flags};
+use ptrace::helpers;
+use syscall::{escape_bytes, HexStyle};
+
+
+/// maximum number of elements decoded from an array of structures
+const MAX_ELEMENTS: usize = 32;
+
+
+/// Defines the structures that arguments can point to.
+#[derive(Clone, Copy)]
+pub enum StructType {
+
+    /// `struct stat` as used by `stat`, `fstat` and `newfstatat`
+    Stat,
+
+    /// `struct stat64` as used by `stat64` and the like on 32-bit architectures
+    Stat64,
+    Timespec,
+    Sockaddr,
+    Iovec,
+    Pollfd,
+    EpollEvent,
+
+    /// `struct rlimit` with fields the size of a long
+    Rlimit,
+
+    /// `struct rlimit64` as used by `prlimit64`
+    Rlimit64,
+
+    /// `struct mmap_arg_struct` holding the arguments of `old_mmap` on i386
+    MmapArgStruct,
+}
+
+
+/// Whether a structure is passed to the kernel, and decoded on SYS_ENTER, or
+/// filled in by the kernel, and decoded on SYS_EXIT if the syscall succeeded.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Dir {
+    In,
+    Out,
+
+    /// passed to the kernel and updated in place, such as the `revents` of a `pollfd`,
+    /// and decoded on both
+    InOut,
+}
+
+
+/// How many structures an argument points to, which for arrays is given either by
+/// another argument or by the return value.
+#[derive(Clone, Copy)]
+pub enum Count {
+    One,
+    Arg(usize),
+    Ret,
+}
+
+
+/// Defines a decoded structure, or a field of one.
+#[derive(Clone)]
+pub enum Value {
+    Int(i64),
+    Uint(u64),
+
+    /// a symbolic value such as `S_IFREG|0644`, or an address
+    Sym(String),
+
+    /// an escaped string, quoted in text
+    Str(String),
+
+    /// a value wrapped in a call in text, such as `htons(80)`, and left bare in JSON
+    Call(&'static str, Box<Value>),
+
+    /// fields in order, of which only as many as given are shown in text
+    Struct(Vec<(&'static str, Value)>, usize),
+
+    /// elements, and whether the array was cut short
+    Array(Vec<Value>, bool),
+}
+
+
+impl fmt::Display for Value {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Uint(value) => write!(f, "{}", value),
+            Value::Sym(value) => write!(f, "{}", value),
+            Value::Str(value) => write!(f, "\"{}\"", value),
+            Value::Call(func, value) => write!(f, "{}({})", func, value),
+            Value::Struct(fields, shown) => {
+                let mut parts: Vec<String> = fields
+                    .iter()
+                    .take(*shown)
+                    .map(|(name, value)| format!("{}={}", name, value))
+                    .collect();
+                if fields.len() > *shown {
+                    parts.push("...".to_string());
+                }
+                write!(f, "{{{}}}", parts.join(", "))
+            },
+            Value::Array(elems, truncated) => {
+                let mut parts: Vec<String> = elems.iter().map(Value::to_string).collect();
+                if *truncated {
+                    parts.push("...".to_string());
+                }
+                write!(f, "[{}]", parts.join(", "))
+            },
+        }
+    }
+}
+
+
+impl Serialize for Value {
+
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Int(value) => serializer.serialize_i64(*value),
+            Value::Uint(value) => serializer.serialize_u64(*value),
+            Value::Sym(value) | Value::Str(value) => serializer.serialize_str(value),
+            Value::Call(_, value) => value.serialize(serializer),
+            Value::Struct(fields, _) => {
+                let mut map = serializer.serialize_map(Some(fields.len()))?;
+                for (name, value) in fields {
+                    map.serialize_entry(name, value)?;
+                }
+                map.end()
+            },
+            Value::Array(elems, _) => {
+                let mut seq = serializer.serialize_seq(Some(elems.len()))?;
+                for elem in elems {
+                    seq.serialize_element(elem)?;
+                }
+                seq.end()
+            },
+        }
+    }
+}
+
+
+impl StructType {
+
+    /// `size()` returns the size of the structure for the given architecture, or `None`
+    /// if it is not decoded for it. The size of a `sockaddr` depends on its family.
+    pub fn size(self, arch: Arch) -> Option<usize> {
+        let size = match self {
+            StructType::Stat => match arch {
+                Arch::X86_64 | Arch::X32 => 144,
+                Arch::Aarch64 | Arch::Riscv64 => 128,
+                Arch::I386 => return None,
+            },
+            StructType::Stat64 => match arch {
+                Arch::I386 => 96,
+                _ => return None,
+            },
+            StructType::Timespec => long_size(arch) * 2,
+            StructType::Sockaddr => 2,
+            StructType::Iovec => ptr_size(arch) * 2,
+            StructType::Pollfd => 8,
+            StructType::EpollEvent => match arch {
+                Arch::Aarch64 | Arch::Riscv64 => 16,
+                _ => 12,
+            },
+            StructType::Rlimit => long_size(arch) * 2,
+            StructType::Rlimit64 => 16,
+            StructType::MmapArgStruct => match arch {
+                Arch::I386 => 24,
+                _ => return None,
+            },
+        };
+        Some(size)
+    }
+
+
+    /// `decode()` decodes a single structure from its raw bytes, as laid out for the
+    /// given architecture, with the fields passed in the given direction. Returns `None`
+    /// if too few bytes are given, or if the kernel left nothing of note in a structure
+    /// it updated, such as a `pollfd` without `revents`.
+    pub fn decode(self, arch: Arch, dir: Dir, bytes: &[u8]) -> Option<Value> {
+        if bytes.len() < self.size(arch)? {
+            return None;
+        }
+        let value = match self {
+            StructType::Stat => decode_stat(arch, bytes),
+            StructType::Stat64 => decode_stat64(bytes),
+            StructType::Timespec => decode_timespec(arch, bytes),
+            StructType::Sockaddr => decode_sockaddr(bytes),
+            StructType::Iovec => {
+                let width = ptr_size(arch);
+                Value::Struct(vec![
+                    ("iov_base", pointer(uint_at(bytes, 0, width))),
+                    ("iov_len", Value::Uint(uint_at(bytes, width, width))),
+                ], 2)
+            },
+            StructType::Pollfd => {
+                let fd = ("fd", Value::Int(i64::from(uint_at(bytes, 0, 4) as i32)));
+                match dir {
+                    Dir::Out => match uint_at(bytes, 6, 2) {
+                        0 => return None,
+                        revents => Value::Struct(vec![fd, ("revents", flags(&POLL_EVENTS, arch, revents))], 2),
+                    },
+                    _ => Value::Struct(vec![fd, ("events", flags(&POLL_EVENTS, arch, uint_at(bytes, 4, 2)))], 2),
+                }
+            },
+            StructType::EpollEvent => {
+                let data = match arch {
+                    Arch::Aarch64 | Arch::Riscv64 => 8,
+                    _ => 4,
+                };
+                Value::Struct(vec![
+                    ("events", flags(&EPOLL_EVENTS, arch, uint_at(bytes, 0, 4))),
+                    ("data", Value::Sym(format!("{:#x}", uint_at(bytes, data, 8)))),
+                ], 2)
+            },
+            StructType::Rlimit => decode_rlimit(bytes, long_size(arch)),
+            StructType::Rlimit64 => decode_rlimit(bytes, 8),
+            StructType::MmapArgStruct => {
+                let u32_at = |off| uint_at(bytes, off, 4);
+                Value::Struct(vec![
+                    ("addr", pointer(u32_at(0))),
+                    ("len", Value::Uint(u32_at(4))),
+                    ("prot", flags(&args::MMAP_PROT, arch, u32_at(8))),
+                    ("flags", flags(&args::MMAP_FLAGS, arch, u32_at(12))),
+                    ("fd", Value::Int(int_at(bytes, 16, 4))),
+                    ("offset", Value::Uint(u32_at(20))),
+                ], 6)
+            },
+        };
+        Some(value)
+    }
+}
+
+
+/// `read()` reads and decodes the structures an argument points to from tracee memory,
+/// with `count` set for arrays. Arrays are cut short after `MAX_ELEMENTS` elements, and
+/// leave out elements that are not decoded in the given direction.
+pub fn read(pid: pid_t, ty: StructType, arch: Arch, dir: Dir, addr: u64, count: Option<u64>) -> io::Result<Value> {
+    let undecodable = || io::Error::other("structure not decoded for architecture");
+    let size = ty.size(arch).ok_or_else(undecodable)?;
+
+    // a sockaddr is only as long as its family requires
+    let size = match ty {
+        StructType::Sockaddr => {
+            let family = helpers::read_memory(pid, addr, size)?;
+            sockaddr_size(uint_at(&family, 0, 2))
+        },
+        _ => size,
+    };
+
+    let count = match count {
+        None => return ty.decode(arch, dir, &helpers::read_memory(pid, addr, size)?).ok_or_else(undecodable),
+        Some(count) => count as usize,
+    };
+    let bytes = helpers::read_memory(pid, addr, size * count.min(MAX_ELEMENTS))?;
+    Ok(decode_array(ty, arch, dir, &bytes, size, count))
+}
+
+
+/// `decode_array()` decodes an array of `count` structures of `size` bytes each from the
+/// raw bytes of those that were read, of which no more than `MAX_ELEMENTS` are decoded.
+fn decode_array(ty: StructType, arch: Arch, dir: Dir, bytes: &[u8], size: usize, count: usize) -> Value {
+    let elems = bytes.chunks(size)
+                     .take(MAX_ELEMENTS)
+                     .filter_map(|chunk| ty.decode(arch, dir, chunk))
+                     .collect();
+    Value::Array(elems, count > MAX_ELEMENTS)
+}
+
+
+/// `long_size()` returns the size of a kernel long for tasks of the architecture.
+fn long_size(arch: Arch) -> usize {
+    match arch {
+        Arch::I386 => 4,
+        _ => 8,
+    }
+}
+
+
+/// `ptr_size()` returns the size of a pointer for tasks of the architecture, which
+/// unlike a kernel long is only 32 bits wide for x32 tasks.
+fn ptr_size(arch: Arch) -> usize {
+    match arch {
+        Arch::I386 | Arch::X32 => 4,
+        _ => 8,
+    }
+}
+
+
+/// `uint_at()` reads a little-endian unsigned integer of the given width at an offset.
+fn uint_at(bytes: &[u8], off: usize, width: usize) -> u64 {
+    bytes[off..off + width]
+        .iter()
+        .rev()
+        .fold(0, |acc, &byte| acc << 8 | u64::from(byte))
+}
+
+
+/// `int_at()` reads a little-endian signed integer of the given width at an offset.
+fn int_at(bytes: &[u8], off: usize, width: usize) -> i64 {
+    let shift = 64 - width * 8;
+    ((uint_at(bytes, off, width) << shift) as i64) >> shift
+}
+
+
+/// `pointer()` renders an address in tracee memory.
+fn pointer(addr: u64) -> Value {
+    Value::Sym(args::render(ArgType::Ptr, Arch::native(), addr).unwrap_or_default())
+}
+
+
+/// `flags()` renders a bitmask field.
+fn flags(flags: &'static Flags, arch: Arch, value: u64) -> Value {
+    Value::Sym(args::render(ArgType::Flags(flags), arch, value).unwrap_or_default())
+}
+
+
+/// `timespec()` builds a `struct timespec` from its fields.
+fn timespec(sec: i64, nsec: i64) -> Value {
+    Value::Struct(vec![("tv_sec", Value::Int(sec)), ("tv_nsec", Value::Int(nsec))], 2)
+}
+
+
+/// `decode_timespec()` decodes a `struct timespec`, whose fields are longs.
+fn decode_timespec(arch: Arch, bytes: &[u8]) -> Value {
+    let width = long_size(arch);
+    timespec(int_at(bytes, 0, width), int_at(bytes, width, width))
+}
+
+
+/// `stat()` builds a `struct stat` from its mode, size and the fields in `STAT_FIELDS`,
+/// of which only the mode and size are shown in text, as in strace.
+fn stat(fields: [u64; 9], times: [(i64, i64); 3]) -> Value {
+    let mut values: Vec<(&'static str, Value)> = vec![
+        ("st_mode", Value::Sym(args::render(ArgType::Mode, Arch::native(), fields[0]).unwrap_or_default())),
+        ("st_size", Value::Int(fields[1] as i64)),
+    ];
+    for (&name, &value) in STAT_FIELDS.iter().zip(fields[2..].iter()) {
+        values.push((name, Value::Uint(value)));
+    }
+    for (&name, &(sec, nsec)) in STAT_TIMES.iter().zip(times.iter()) {
+        values.push((name, timespec(sec, nsec)));
+    }
+    Value::Struct(values, 2)
+}
+
+
+static STAT_FIELDS: &[&str] = &["st_dev", "st_ino", "st_nlink", "st_uid", "st_gid", "st_rdev", "st_blocks"];
+static STAT_TIMES: &[&str] = &["st_atim", "st_mtim", "st_ctim"];
+
+
+/// `decode_stat()` decodes a `struct stat` as laid out on x86_64, or on architectures
+/// using the generic layout such as aarch64 and riscv64.
+fn decode_stat(arch: Arch, bytes: &[u8]) -> Value {
+    let u32_at = |off| uint_at(bytes, off, 4);
+    let u64_at = |off| uint_at(bytes, off, 8);
+    let times = [
+        (int_at(bytes, 72, 8), int_at(bytes, 80, 8)),
+        (int_at(bytes, 88, 8), int_at(bytes, 96, 8)),
+        (int_at(bytes, 104, 8), int_at(bytes, 112, 8)),
+    ];
+    match arch {
+        Arch::X86_64 | Arch::X32 => stat(
+            [u32_at(24), u64_at(48), u64_at(0), u64_at(8), u64_at(16), u32_at(28), u32_at(32), u64_at(40), u64_at(64)],
+            times),
+        _ => stat(
+            [u32_at(16), u64_at(48), u64_at(0), u64_at(8), u32_at(20), u32_at(24), u32_at(28), u64_at(32), u64_at(64)],
+            times),
+    }
+}
+
+
+/// `decode_stat64()` decodes a `struct stat64` as laid out on i386, where it is packed.
+fn decode_stat64(bytes: &[u8]) -> Value {
+    let u32_at = |off| uint_at(bytes, off, 4);
+    let u64_at = |off| uint_at(bytes, off, 8);
+    let times = [
+        (int_at(bytes, 64, 4), int_at(bytes, 68, 4)),
+        (int_at(bytes, 72, 4), int_at(bytes, 76, 4)),
+        (int_at(bytes, 80, 4), int_at(bytes, 84, 4)),
+    ];
+    stat([u32_at(16), u64_at(44), u64_at(0), u64_at(88), u32_at(20), u32_at(24), u32_at(28), u64_at(32), u64_at(56)],
+         times)
+}
+
+
+/// `decode_rlimit()` decodes a `struct rlimit` with fields of the given width, where
+/// all bits set denotes no limit.
+fn decode_rlimit(bytes: &[u8], width: usize) -> Value {
+    let infinity = if width == 8 { u64::MAX } else { u64::from(u32::MAX) };
+    let limit = |off| match uint_at(bytes, off, width) {
+        value if value == infinity => Value::Sym("RLIM_INFINITY".to_string()),
+        value => Value::Uint(value),
+    };
+    Value::Struct(vec![("rlim_cur", limit(0)), ("rlim_max", limit(width))], 2)
+}
+
+
+/// `sockaddr_size()` returns the size of the socket address for a family, falling back
+/// to the generic `struct sockaddr` for other families.
+fn sockaddr_size(family: u64) -> usize {
+    match family as i32 {
+        libc::AF_UNIX => 110,
+        libc::AF_INET => 16,
+        libc::AF_INET6 => 28,
+        libc::AF_NETLINK => 12,
+        _ => 16,
+    }
+}
+
+
+/// `decode_sockaddr()` decodes a socket address of the families commonly seen, showing
+/// only the family for others. Ports and addresses are in network byte order.
+fn decode_sockaddr(bytes: &[u8]) -> Value {
+    let family = uint_at(bytes, 0, 2);
+    let mut fields = vec![
+        ("sa_family", Value::Sym(args::render(ArgType::Enum(args::SOCKET_DOMAINS), Arch::native(), family)
+                                     .unwrap_or_default())),
+    ];
+    let port = |off: usize| Value::Call("htons", Box::new(Value::Uint(u64::from(bytes[off]) << 8 | u64::from(bytes[off + 1]))));
+
+    match family as i32 {
+        libc::AF_UNIX if bytes.len() > 2 => {
+            let path = &bytes[2..];
+            let path = match path.iter().position(|&byte| byte == 0) {
+                Some(0) if path.len() > 1 => {
+
+                    // abstract sockets begin with a NUL byte, shown as `@` like in ss(8)
+                    let end = path[1..].iter().position(|&byte| byte == 0).map_or(path.len(), |end| end + 1);
+                    format!("@{}", escape_bytes(&path[1..end], HexStyle::Never))
+                },
+                Some(end) => escape_bytes(&path[..end], HexStyle::Never),
+                None => escape_bytes(path, HexStyle::Never),
+            };
+            fields.push(("sun_path", Value::Str(path)));
+        },
+        libc::AF_INET if bytes.len() >= 8 => {
+            let addr = Ipv4Addr::new(bytes[4], bytes[5], bytes[6], bytes[7]);
+            fields.push(("sin_port", port(2)));
+            fields.push(("sin_addr", Value::Call("inet_addr", Box::new(Value::Str(addr.to_string())))));
+        },
+        libc::AF_INET6 if bytes.len() >= 24 => {
+            let mut octets = [0; 16];
+            octets.copy_from_slice(&bytes[8..24]);
+            fields.push(("sin6_port", port(2)));
+            let flowinfo = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+            fields.push(("sin6_flowinfo", Value::Call("htonl", Box::new(Value::Uint(u64::from(flowinfo))))));
+            fields.push(("sin6_addr", Value::Call("inet_pton", Box::new(Value::Str(Ipv6Addr::from(octets).to_string())))));
+        },
+        libc::AF_NETLINK if bytes.len() >= 12 => {
+            fields.push(("nl_pid", Value::Uint(uint_at(bytes, 4, 4))));
+            fields.push(("nl_groups", Value::Sym(format!("{:#010x}", uint_at(bytes, 8, 4)))));
+        },
+        _ => {},
+    }
+    let shown = fields.len();
+    Value::Struct(fields, shown)
+}
+
+
+static POLL_EVENTS: Flags = Flags {
+    base: &[],
+    mask: 0,
+    bits: &[
+        (libc::POLLIN as u64,     "POLLIN"),
+        (libc::POLLPRI as u64,    "POLLPRI"),
+        (libc::POLLOUT as u64,    "POLLOUT"),
+        (libc::POLLERR as u64,    "POLLERR"),
+        (libc::POLLHUP as u64,    "POLLHUP"),
+        (libc::POLLNVAL as u64,   "POLLNVAL"),
+        (libc::POLLRDNORM as u64, "POLLRDNORM"),
+        (libc::POLLRDBAND as u64, "POLLRDBAND"),
+        (libc::POLLWRNORM as u64, "POLLWRNORM"),
+        (libc::POLLWRBAND as u64, "POLLWRBAND"),
+        (libc::POLLRDHUP as u64,  "POLLRDHUP"),
+    ],
+};
+
+
+static EPOLL_EVENTS: Flags = Flags {
+    base: &[],
+    mask: 0,
+    bits: &[
+        (libc::EPOLLIN as u64,             "EPOLLIN"),
+        (libc::EPOLLPRI as u64,            "EPOLLPRI"),
+        (libc::EPOLLOUT as u64,            "EPOLLOUT"),
+        (libc::EPOLLERR as u64,            "EPOLLERR"),
+        (libc::EPOLLHUP as u64,            "EPOLLHUP"),
+        (libc::EPOLLRDNORM as u64,         "EPOLLRDNORM"),
+        (libc::EPOLLRDBAND as u64,         "EPOLLRDBAND"),
+        (libc::EPOLLWRNORM as u64,         "EPOLLWRNORM"),
+        (libc::EPOLLWRBAND as u64,         "EPOLLWRBAND"),
+        (libc::EPOLLMSG as u64,            "EPOLLMSG"),
+        (libc::EPOLLRDHUP as u64,          "EPOLLRDHUP"),
+        (libc::EPOLLEXCLUSIVE as u64,      "EPOLLEXCLUSIVE"),
+        (libc::EPOLLWAKEUP as u64,         "EPOLLWAKEUP"),
+        (libc::EPOLLONESHOT as u64,        "EPOLLONESHOT"),
+        (libc::EPOLLET as u32 as u64,      "EPOLLET"),
+    ],
+};
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use super::*;
+
+
+    /// `layout()` lays out a structure of `size` bytes from little-endian fields, given by
+    /// offset, width and value.
+    fn layout(size: usize, fields: &[(usize, usize, u64)]) -> Vec<u8> {
+        let mut bytes = vec![0; size];
+        for &(off, width, value) in fields {
+            bytes[off..off + width].copy_from_slice(&value.to_le_bytes()[..width]);
+        }
+        bytes
+    }
+
+
+    /// `decode()` decodes a structure passed to the kernel, in text and in JSON.
+    fn decode(ty: StructType, arch: Arch, bytes: &[u8]) -> (String, String) {
+        let value = ty.decode(arch, Dir::In, bytes).unwrap();
+        (value.to_string(), serde_json::to_string(&value).unwrap())
+    }
+
+
+    /// every field of a `struct stat`, in the order they are decoded in
+    static STAT_JSON: &str = concat!(
+        r#"{"st_mode":"S_IFREG|0644","st_size":1234,"st_dev":2049,"st_ino":131,"st_nlink":1,"#,
+        r#""st_uid":1000,"st_gid":100,"st_rdev":0,"st_blocks":8,"#,
+        r#""st_atim":{"tv_sec":1700000001,"tv_nsec":1},"st_mtim":{"tv_sec":1700000002,"tv_nsec":2},"#,
+        r#""st_ctim":{"tv_sec":1700000003,"tv_nsec":3}}"#,
+    );
+
+
+    #[test]
+    fn stat_layouts() {
+        let mode = libc::S_IFREG as u64 | 0o644;
+        let times = [(72, 8, 1_700_000_001), (80, 8, 1), (88, 8, 1_700_000_002), (96, 8, 2),
+                     (104, 8, 1_700_000_003), (112, 8, 3)];
+
+        let x86_64 = [(0, 8, 2049), (8, 8, 131), (16, 8, 1), (24, 4, mode), (28, 4, 1000),
+                      (32, 4, 100), (48, 8, 1234), (56, 8, 4096), (64, 8, 8)];
+        let aarch64 = [(0, 8, 2049), (8, 8, 131), (16, 4, mode), (20, 4, 1), (24, 4, 1000),
+                       (28, 4, 100), (48, 8, 1234), (56, 4, 4096), (64, 8, 8)];
+        for &(arch, size, fields) in &[(Arch::X86_64, 144, &x86_64), (Arch::Aarch64, 128, &aarch64)] {
+            let bytes = layout(size, &[&fields[..], &times[..]].concat());
+            let (text, json) = decode(StructType::Stat, arch, &bytes);
+            assert_eq!(text, "{st_mode=S_IFREG|0644, st_size=1234, ...}");
+            assert_eq!(json, STAT_JSON);
+            assert!(StructType::Stat.decode(arch, Dir::In, &bytes[..size - 1]).is_none());
+        }
+
+        // i386 packs `struct stat64`, and keeps the full inode number at its end
+        let i386 = layout(96, &[(0, 8, 2049), (12, 4, 131), (16, 4, mode), (20, 4, 1), (24, 4, 1000),
+                                (28, 4, 100), (44, 8, 1234), (52, 4, 4096), (56, 8, 8),
+                                (64, 4, 1_700_000_001), (68, 4, 1), (72, 4, 1_700_000_002), (76, 4, 2),
+                                (80, 4, 1_700_000_003), (84, 4, 3), (88, 8, 131)]);
+        let (text, json) = decode(StructType::Stat64, Arch::I386, &i386);
+        assert_eq!(text, "{st_mode=S_IFREG|0644, st_size=1234, ...}");
+        assert_eq!(json, STAT_JSON);
+        assert!(StructType::Stat.size(Arch::I386).is_none());
+        assert!(StructType::Stat64.size(Arch::X86_64).is_none());
+    }
+
+
+    #[test]
+    fn sockaddr_families() {
+        let sockaddr = |bytes: &[u8]| decode(StructType::Sockaddr, Arch::X86_64, bytes).0;
+
+        let mut unix = vec![1, 0];
+        unix.extend_from_slice(b"/run/app.sock\0\0\0");
+        assert_eq!(sockaddr(&unix), r#"{sa_family=AF_UNIX, sun_path="/run/app.sock"}"#);
+
+        // abstract sockets are not NUL-terminated, but may be padded with NULs
+        assert_eq!(sockaddr(b"\x01\x00\x00app\x00\x00"), r#"{sa_family=AF_UNIX, sun_path="@app"}"#);
+
+        let inet = [2, 0, 0x1f, 0x90, 127, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(sockaddr(&inet), r#"{sa_family=AF_INET, sin_port=htons(8080), sin_addr=inet_addr("127.0.0.1")}"#);
+
+        let mut inet6 = vec![10, 0, 0x01, 0xbb, 0, 0, 0, 7, 0x20, 0x01, 0x0d, 0xb8];
+        inet6.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0]);
+        assert_eq!(sockaddr(&inet6), concat!(r#"{sa_family=AF_INET6, sin6_port=htons(443), sin6_flowinfo=htonl(7), "#,
+                                             r#"sin6_addr=inet_pton("2001:db8::1")}"#));
+
+        let netlink = layout(12, &[(0, 2, libc::AF_NETLINK as u64), (4, 4, 1234), (8, 4, 5)]);
+        assert_eq!(sockaddr(&netlink), "{sa_family=AF_NETLINK, nl_pid=1234, nl_groups=0x00000005}");
+
+        // only the family is shown for others, and ports stay numbers in JSON
+        assert_eq!(sockaddr(&[17, 0, 0, 0]), "{sa_family=AF_PACKET}");
+        assert_eq!(decode(StructType::Sockaddr, Arch::X86_64, &inet).1,
+                   r#"{"sa_family":"AF_INET","sin_port":8080,"sin_addr":"127.0.0.1"}"#);
+    }
+
+
+    #[test]
+    fn long_sized_fields() {
+        let timespec = layout(16, &[(0, 8, 1), (8, 8, 500_000_000)]);
+        assert_eq!(decode(StructType::Timespec, Arch::X86_64, &timespec).0, "{tv_sec=1, tv_nsec=500000000}");
+        let timespec = layout(8, &[(0, 4, u64::from(u32::MAX)), (4, 4, 500)]);
+        assert_eq!(decode(StructType::Timespec, Arch::I386, &timespec).0, "{tv_sec=-1, tv_nsec=500}");
+
+        // all bits set is no limit, for the width of a long
+        let rlimit = layout(16, &[(0, 8, 1024), (8, 8, u64::MAX)]);
+        assert_eq!(decode(StructType::Rlimit, Arch::X86_64, &rlimit).0, "{rlim_cur=1024, rlim_max=RLIM_INFINITY}");
+        let rlimit = layout(8, &[(0, 4, 1024), (4, 4, u64::from(u32::MAX))]);
+        assert_eq!(decode(StructType::Rlimit, Arch::I386, &rlimit).0, "{rlim_cur=1024, rlim_max=RLIM_INFINITY}");
+        let rlimit64 = layout(16, &[(0, 8, u64::from(u32::MAX)), (8, 8, u64::MAX)]);
+        assert_eq!(decode(StructType::Rlimit64, Arch::I386, &rlimit64).0,
+                   "{rlim_cur=4294967295, rlim_max=RLIM_INFINITY}");
+    }
+
+
+    #[test]
+    fn epoll_event_layouts() {
+        let events = (libc::EPOLLIN | libc::EPOLLOUT) as u64;
+
+        // packed on x86, with the data following the events directly
+        let packed = layout(12, &[(0, 4, events), (4, 8, 0xdead_beef)]);
+        assert_eq!(decode(StructType::EpollEvent, Arch::X86_64, &packed).0, "{events=EPOLLIN|EPOLLOUT, data=0xdeadbeef}");
+        let aligned = layout(16, &[(0, 4, events), (8, 8, 0xdead_beef)]);
+        assert_eq!(decode(StructType::EpollEvent, Arch::Aarch64, &aligned).0, "{events=EPOLLIN|EPOLLOUT, data=0xdeadbeef}");
+    }
+
+
+    #[test]
+    fn arrays_cut_short() {
+        let pollfds: Vec<u8> = (0..MAX_ELEMENTS as u64)
+            .flat_map(|fd| layout(8, &[(0, 4, fd), (4, 2, libc::POLLIN as u64)]))
+            .collect();
+        let array = |count| decode_array(StructType::Pollfd, Arch::X86_64, Dir::In, &pollfds, 8, count).to_string();
+        assert!(array(MAX_ELEMENTS).ends_with("{fd=31, events=POLLIN}]"));
+        assert!(array(MAX_ELEMENTS + 1).ends_with("{fd=31, events=POLLIN}, ...]"));
+        let array = decode_array(StructType::Pollfd, Arch::X86_64, Dir::In, &pollfds[..16], 8, 2);
+        assert_eq!(array.to_string(), "[{fd=0, events=POLLIN}, {fd=1, events=POLLIN}]");
+    }
+
+
+    #[test]
+    fn pollfd_in_both_directions() {
+
+        // {fd=3, events=POLLIN, revents=POLLIN|POLLHUP}
+        let ready = [3, 0, 0, 0, 0x01, 0, 0x11, 0];
+        let ty = StructType::Pollfd;
+        assert_eq!(ty.decode(Arch::X86_64, Dir::In, &ready).unwrap().to_string(), "{fd=3, events=POLLIN}");
+        assert_eq!(ty.decode(Arch::X86_64, Dir::Out, &ready).unwrap().to_string(), "{fd=3, revents=POLLIN|POLLHUP}");
+
+        // descriptors without events are left out once the kernel has updated them
+        let idle = [5, 0, 0, 0, 0x01, 0, 0, 0];
+        assert!(ty.decode(Arch::X86_64, Dir::Out, &idle).is_none());
+        assert!(ty.decode(Arch::X86_64, Dir::In, &ready[..4]).is_none());
+    }
+
+
+    #[test]
+    fn old_mmap_arguments() {
+
+        // {addr=NULL, len=8192, prot=PROT_READ|PROT_WRITE, flags=MAP_PRIVATE|MAP_ANONYMOUS, fd=-1, offset=0}
+        let mut bytes = Vec::new();
+        for &word in &[0u32, 8192, 3, 0x22, u32::MAX, 0] {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        let ty = StructType::MmapArgStruct;
+        assert_eq!(ty.decode(Arch::I386, Dir::In, &bytes).unwrap().to_string(),
+                   "{addr=NULL, len=8192, prot=PROT_READ|PROT_WRITE, flags=MAP_PRIVATE|MAP_ANONYMOUS, fd=-1, offset=0}");
+        assert!(ty.decode(Arch::X86_64, Dir::In, &bytes).is_none());
+    }
+}
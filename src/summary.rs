@@ -0,0 +1,191 @@
+//! summary.rs
+//!
+//!     Aggregates traced syscalls into a summary of how often
+//!     each was called, how often it failed, and how much time
+//!     was spent in it, similar to `strace -c`.
+//!
+//!     Time is measured as wall-clock time between SYS_ENTER
+//!     and SYS_EXIT, and syscalls that never returned, such as
+//!     `exit_group`, are counted without adding to it.
+
+use std::fmt;
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use syscall::Syscall;
+
+
+/// Statistics gathered for a single syscall, or for all of them.
+#[derive(Default)]
+struct Stats {
+    calls: u64,
+    errors: u64,
+    total_ns: u64,
+    min_ns: Option<u64>,
+    max_ns: u64,
+}
+
+
+impl Stats {
+
+    fn record(&mut self, syscall: &Syscall) {
+        self.calls += 1;
+        if syscall.failed() {
+            self.errors += 1;
+        }
+        if let Some(duration) = syscall.duration() {
+            let ns = duration.as_nanos() as u64;
+            self.total_ns += ns;
+            self.min_ns = Some(self.min_ns.map_or(ns, |min| min.min(ns)));
+            self.max_ns = self.max_ns.max(ns);
+        }
+    }
+
+    fn merge(&mut self, other: &Stats) {
+        self.calls += other.calls;
+        self.errors += other.errors;
+        self.total_ns += other.total_ns;
+        self.min_ns = match (self.min_ns, other.min_ns) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.max_ns = self.max_ns.max(other.max_ns);
+    }
+}
+
+
+/// Keeps running statistics for every syscall seen while tracing.
+#[derive(Default)]
+pub struct Aggregator {
+    stats: HashMap<String, Stats>,
+    unknown: u64,
+}
+
+
+impl Aggregator {
+
+    /// `record()` adds a completed or unfinished syscall to the statistics.
+    pub fn record(&mut self, syscall: &Syscall) {
+        if syscall.unknown() {
+            self.unknown += 1;
+        }
+        self.stats
+            .entry(syscall.name().to_string())
+            .or_default()
+            .record(syscall);
+    }
+
+    /// `summary()` creates a summary of every syscall seen so far, sorted by
+    /// the time spent in them, then by the number of calls.
+    pub fn summary(&self) -> Summary {
+        let mut total = Stats::default();
+        for stats in self.stats.values() {
+            total.merge(stats);
+        }
+
+        let mut syscalls: Vec<SummaryRow> = self.stats
+            .iter()
+            .map(|(name, stats)| SummaryRow::new(name, stats, total.total_ns))
+            .collect();
+        syscalls.sort_by(|a, b| {
+            b.total_ns.cmp(&a.total_ns)
+                .then(b.calls.cmp(&a.calls))
+                .then(a.name.cmp(&b.name))
+        });
+
+        Summary {
+            syscalls,
+            total: SummaryRow::new("total", &total, total.total_ns),
+            unknown: self.unknown,
+        }
+    }
+}
+
+
+/// A row of the summary table. Times are kept in nanoseconds.
+#[derive(Serialize)]
+pub struct SummaryRow {
+    name: String,
+    calls: u64,
+    errors: u64,
+    total_ns: u64,
+    avg_ns: u64,
+    min_ns: u64,
+    max_ns: u64,
+    percent: f64,
+}
+
+
+impl SummaryRow {
+
+    fn new(name: &str, stats: &Stats, overall_ns: u64) -> Self {
+        let percent = if overall_ns == 0 {
+            0.0
+        } else {
+            stats.total_ns as f64 * 100.0 / overall_ns as f64
+        };
+        Self {
+            name: name.to_string(),
+            calls: stats.calls,
+            errors: stats.errors,
+            total_ns: stats.total_ns,
+            avg_ns: stats.total_ns.checked_div(stats.calls).unwrap_or(0),
+            min_ns: stats.min_ns.unwrap_or(0),
+            max_ns: stats.max_ns,
+            percent,
+        }
+    }
+}
+
+
+impl fmt::Display for SummaryRow {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let errors = match self.errors {
+            0 => String::new(),
+            errors => errors.to_string(),
+        };
+        write!(f, "{:>6.2} {:>11.6} {:>11} {:>11} {:>11} {:>9} {:>9} {}",
+               self.percent,
+               self.total_ns as f64 / 1e9,
+               self.avg_ns / 1000,
+               self.min_ns / 1000,
+               self.max_ns / 1000,
+               self.calls,
+               errors,
+               self.name)
+    }
+}
+
+
+/// Defines the summary of a whole trace, written out once tracing is done. Calls to
+/// syscalls missing from the syscall table are counted in `unknown`, as they may
+/// indicate that the table is outdated for the kernel being traced.
+#[derive(Serialize)]
+pub struct Summary {
+    syscalls: Vec<SummaryRow>,
+    total: SummaryRow,
+    unknown: u64,
+}
+
+
+impl fmt::Display for Summary {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let separator = format!("{} {} {} {} {} {} {} {}",
+                                "-".repeat(6), "-".repeat(11), "-".repeat(11), "-".repeat(11),
+                                "-".repeat(11), "-".repeat(9), "-".repeat(9), "-".repeat(16));
+        writeln!(f, "% time     seconds  usecs/call   min usecs   max usecs     calls    errors syscall")?;
+        writeln!(f, "{}", separator)?;
+        for row in &self.syscalls {
+            writeln!(f, "{}", row)?;
+        }
+        writeln!(f, "{}", separator)?;
+        write!(f, "{}", self.total)?;
+        if self.unknown > 0 {
+            write!(f, "\nwarning: {} calls to syscalls with unknown numbers", self.unknown)?;
+        }
+        Ok(())
+    }
+}
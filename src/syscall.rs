@@ -2,77 +2,583 @@
 //!
 //!     Defines struct interface for system calls.
 //!
-//!     Implements a parser for unistd.h's syscall table
-//!     in order to generate system calls with correct names.
+//!     Syscall numbers are resolved into names through the tables
+//!     embedded for each architecture, or optionally through a
+//!     parser for a unistd.h syscall table given by the user.
 
 use std::io;
 use std::fmt;
 use std::fs::File;
+use std::ffi::CStr;
 use std::io::prelude::*;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
 
+use libc::c_int;
+use nix::errno::Errno;
 use regex::Regex;
-use serde::Serialize;
-use serde_json::Result;
+use serde::{Serialize, Serializer};
 
-// path to unistd file with syscall number definitions
-static SYSCALL_TABLE: &str = "/usr/include/asm/unistd_64.h";
+use sysent;
+use args::{self, ArgType};
+use fds::FdTable;
+use event::{self, Event};
+use sink::EventSink;
+use filter::Filter;
+use arch::Arch;
+use arch::regs::Regs;
+use structs::Value;
 
 // regex for parsing macro definitions of syscall numbers
 static SYSCALL_REGEX: &str = r"#define\s*__NR_(\w+)\s*(\d+)";
 
+// largest errno value the kernel will return negated from a syscall
+static MAX_ERRNO: i64 = 4095;
+
 // type alias for syscall table hashmap
 type SyscallTable = HashMap<u64, String>;
 
 
+/// A string argument decoded from tracee memory, stored escaped.
+#[derive(Serialize)]
+pub struct StringArg {
+    value: String,
+    truncated: bool,
+}
+
+
+impl fmt::Display for StringArg {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"{}\"", self.value)?;
+        if self.truncated {
+            write!(f, "...")?;
+        }
+        Ok(())
+    }
+}
+
+
+/// How strings and buffers read from tracee memory are escaped, as selected with `-x`.
+#[derive(Clone, Copy, PartialEq)]
+pub enum HexStyle {
+
+    /// escape non-printable bytes in octal
+    Never,
+
+    /// escape every byte in hex if any of them is neither printable nor whitespace, as with `-x`
+    NonAscii,
+
+    /// escape every byte in hex, as with `-xx`
+    All,
+}
+
+
+/// A buffer transferred by an I/O syscall, such as the data passed to `write` or
+/// returned by `read`, serialized as base64. `data` holds as much of it as was read
+/// from tracee memory, and `truncated` is set if the transfer was any longer.
+#[derive(Serialize)]
+pub struct Buffer {
+    #[serde(rename = "base64", serialize_with = "serialize_base64")]
+    data: Vec<u8>,
+    truncated: bool,
+
+    /// number of bytes shown in text output
+    #[serde(skip)]
+    limit: usize,
+    #[serde(skip)]
+    hex: HexStyle,
+
+    /// set if the buffer is also shown as a full hexdump, as with `-e read=FD`
+    #[serde(skip)]
+    dump: bool,
+}
+
+
+impl Buffer {
+
+    /// `new()` initializes a buffer from the bytes read out of the `len` that were
+    /// transferred, of which `limit` are shown in text output.
+    pub fn new(data: Vec<u8>, len: usize, limit: usize, hex: HexStyle, dump: bool) -> Self {
+        let truncated = data.len() < len;
+        Self { data, truncated, limit, hex, dump }
+    }
+
+
+    /// `hexdump()` renders the whole buffer as strace does with `-e read=FD`, with the
+    /// offset, hex and printable characters of 16 bytes on each line, e.g.
+    /// ` | 00000  68 65 6c 6c 6f 0a                                hello.           |`.
+    pub fn hexdump(&self) -> Vec<String> {
+        self.data
+            .chunks(16)
+            .enumerate()
+            .map(|(line, chunk)| {
+                let mut text = format!(" | {:05x}  ", line * 16);
+                for idx in 0..16 {
+                    match chunk.get(idx) {
+                        Some(byte) => text.push_str(&format!("{:02x} ", byte)),
+                        None => text.push_str("   "),
+                    }
+                    if idx == 7 {
+                        text.push(' ');
+                    }
+                }
+                let ascii: String = chunk
+                    .iter()
+                    .map(|&byte| match byte {
+                        0x20..=0x7e => byte as char,
+                        _ => '.',
+                    })
+                    .collect();
+                text.push_str(&format!(" {:<16} |", ascii));
+                text
+            })
+            .collect()
+    }
+}
+
+
+impl fmt::Display for Buffer {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let shown = &self.data[..self.data.len().min(self.limit)];
+        write!(f, "\"{}\"", escape_bytes(shown, self.hex))?;
+        if self.truncated || shown.len() < self.data.len() {
+            write!(f, "...")?;
+        }
+        Ok(())
+    }
+}
+
+
 /// Defines an arbitrary syscall, with support for de/serialization
-/// with serde_json.
+/// with serde_json. Data decoded for an argument is keyed by its index,
+/// while `args` always retains the raw register values.
 #[derive(Serialize)]
 pub struct Syscall {
+
+    /// thread group (process) of the caller
+    pid: i32,
+
+    /// thread that made the syscall
+    tid: i32,
     number: u64,
+
+    /// name from the syscall table, or one derived from the number if `unknown`
     name: String,
+    arch: Arch,
+    #[serde(skip_serializing_if = "is_false")]
+    unknown: bool,
+
+    /// raw arguments, with any passed in a pair of registers joined into one
     args: Vec<u64>,
-}
 
+    /// string and path arguments read from tracee memory
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    strings: BTreeMap<usize, StringArg>,
 
-/// SyscallManager stores a vector of Syscalls and manages a HashMap
-/// that stores syscall num and name mappings.
-#[derive(Serialize)]
-#[serde(rename = "syscalls")]
-pub struct SyscallManager {
-    syscalls: Vec<Syscall>,
+    /// arguments of a known type rendered symbolically, such as `O_RDONLY|O_CLOEXEC`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    decoded: BTreeMap<usize, String>,
+
+    /// structures that arguments point to
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    structs: BTreeMap<usize, Value>,
+
+    /// structures that arguments point to as updated by the kernel, such as the
+    /// descriptors `poll` found ready
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    returned: BTreeMap<usize, Value>,
+
+    /// data transferred by I/O syscalls
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    buffers: BTreeMap<usize, Buffer>,
+
+    /// resources that descriptor arguments refer to, if descriptors are tracked
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    fds: BTreeMap<usize, String>,
+
+    /// return value, only set once the syscall has reached SYS_EXIT
+    ret: Option<i64>,
+
+    /// resource that the returned descriptor refers to, if descriptors are tracked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ret_fd: Option<String>,
+
+    /// name of the errno returned, if the syscall failed
+    errno: Option<String>,
 
+    /// set until the syscall has reached SYS_EXIT
+    unfinished: bool,
+
+    /// wall-clock time of SYS_ENTER
+    timestamp_ns: u64,
+
+    /// time spent in the syscall until SYS_EXIT
+    #[serde(rename = "duration_ns", skip_serializing_if = "Option::is_none", serialize_with = "serialize_duration")]
+    duration: Option<Duration>,
+
+    /// register snapshots, only taken if requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entry_regs: Option<Box<Regs>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exit_regs: Option<Box<Regs>>,
     #[serde(skip)]
-    pub _syscall_table: SyscallTable
+    entered: Instant,
+
+    /// set for syscalls that are only followed for their effect on descriptors, and
+    /// are not written out
+    #[serde(skip)]
+    suppressed: bool,
 }
 
 
-impl Default for SyscallManager {
-    fn default() -> Self {
-        let syscall_table = SyscallManager::_parse_syscall_table()
-            .expect("cannot parse syscall table.");
+impl Syscall {
 
-        Self {
-            syscalls: Vec::new(),
-            _syscall_table: syscall_table
+    pub fn pid(&self) -> i32 {
+        self.pid
+    }
+
+    pub fn tid(&self) -> i32 {
+        self.tid
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn args(&self) -> &[u64] {
+        &self.args
+    }
+
+    pub fn arch(&self) -> Arch {
+        self.arch
+    }
+
+    pub fn ret(&self) -> Option<i64> {
+        self.ret
+    }
+
+    pub fn unknown(&self) -> bool {
+        self.unknown
+    }
+
+    pub fn failed(&self) -> bool {
+        self.errno.is_some()
+    }
+
+    pub fn timestamp_ns(&self) -> u64 {
+        self.timestamp_ns
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+
+    /// `fd()` returns the resource a descriptor argument refers to, if known.
+    pub fn fd(&self, idx: usize) -> Option<&str> {
+        self.fds.get(&idx).map(String::as_str)
+    }
+
+    pub fn suppressed(&self) -> bool {
+        self.suppressed
+    }
+
+    pub fn suppress(&mut self) {
+        self.suppressed = true;
+    }
+
+
+    /// `set_string()` records the contents of a string argument read from tracee memory.
+    pub fn set_string(&mut self, idx: usize, bytes: &[u8], truncated: bool, hex: HexStyle) {
+        let value = escape_bytes(bytes, hex);
+        self.strings.insert(idx, StringArg { value, truncated });
+    }
+
+
+    /// `set_struct()` records the structures an argument points to, as decoded from
+    /// tracee memory.
+    pub fn set_struct(&mut self, idx: usize, value: Value) {
+        self.structs.insert(idx, value);
+    }
+
+
+    /// `set_returned()` records the structures an argument points to as updated by the
+    /// kernel, unless it updated none of them.
+    pub fn set_returned(&mut self, idx: usize, value: Value) {
+        if !matches!(value, Value::Array(ref elems, _) if elems.is_empty()) {
+            self.returned.insert(idx, value);
+        }
+    }
+
+
+    /// `set_buffer()` records the data transferred through a buffer argument.
+    pub fn set_buffer(&mut self, idx: usize, buffer: Buffer) {
+        self.buffers.insert(idx, buffer);
+    }
+
+
+    /// `hexdumps()` returns the hexdumps of any buffers that are to be shown in full.
+    pub fn hexdumps(&self) -> Vec<String> {
+        self.buffers
+            .values()
+            .filter(|buffer| buffer.dump)
+            .flat_map(Buffer::hexdump)
+            .collect()
+    }
+
+
+    /// `set_ret_fd()` records the resource the returned descriptor refers to.
+    pub fn set_ret_fd(&mut self, resource: &str) {
+        self.ret_fd = Some(resource.to_string());
+    }
+
+
+    /// `set_entry_regs()` records a snapshot of the registers at SYS_ENTER.
+    pub fn set_entry_regs(&mut self, regs: Regs) {
+        self.entry_regs = Some(Box::new(regs));
+    }
+
+
+    /// `set_exit_regs()` records a snapshot of the registers at SYS_EXIT.
+    pub fn set_exit_regs(&mut self, regs: Regs) {
+        self.exit_regs = Some(Box::new(regs));
+    }
+
+
+    /// `set_return()` records the value from the return register at SYS_EXIT,
+    /// decoding an errno name if the kernel returned a negated error, along with
+    /// the time spent in the syscall since SYS_ENTER.
+    pub fn set_return(&mut self, ret: i64) {
+        self.duration = Some(self.entered.elapsed());
+        self.ret = Some(ret);
+        self.errno = errno_name(ret);
+        self.unfinished = false;
+    }
+
+
+    /// `call()` renders the syscall and its arguments as strace does, e.g.
+    /// `openat(AT_FDCWD, "/etc/ld.so.cache", O_RDONLY|O_CLOEXEC)`. String
+    /// arguments that could not be read are shown as their address instead,
+    /// and the arguments of unknown syscalls are all shown in hex. Descriptors
+    /// are followed by the resource they refer to if known, as in `3</etc/passwd>`,
+    /// and arguments the syscall ignores given the others are left out.
+    pub fn call(&self) -> String {
+        let string_args = sysent::string_args(&self.name);
+        let args: Vec<String> = self.args
+            .iter()
+            .take(args::shown(&self.name, &self.args))
+            .enumerate()
+            .map(|(idx, arg)| match self.strings.get(&idx) {
+                None if self.unknown => format!("{:#x}", arg),
+                Some(string) => string.to_string(),
+                None if self.structs.contains_key(&idx) => self.structs[&idx].to_string(),
+                None if self.buffers.contains_key(&idx) => self.buffers[&idx].to_string(),
+                None if string_args.iter().any(|&(i, _)| i == idx) => match *arg {
+                    0 => "NULL".to_string(),
+                    addr => format!("{:#x}", addr),
+                },
+                None => {
+                    let text = match self.decoded.get(&idx) {
+                        Some(text) => text.clone(),
+                        None => arg.to_string(),
+                    };
+                    match self.fds.get(&idx) {
+                        Some(resource) => format!("{}<{}>", text, resource),
+                        None => text,
+                    }
+                },
+            })
+            .collect();
+        format!("{}({})", self.name, args.join(", "))
+    }
+
+
+    /// `result()` renders the return value as strace does, following the `=`.
+    /// Failed syscalls are shown as `-1 ENOENT (No such file or directory)`, while
+    /// those interrupted for a restart and those that never returned are shown as `?`.
+    /// Structures updated by the kernel follow, as in `1 ([{fd=3, revents=POLLIN}])`.
+    pub fn result(&self) -> String {
+        match (self.ret, &self.errno) {
+            (Some(ret), Some(errno)) if is_restart(-ret) => format!("? {} ({})", errno, errno_desc(-ret)),
+            (Some(ret), Some(errno)) => format!("-1 {} ({})", errno, errno_desc(-ret)),
+            (Some(ret), None) if sysent::returns_addr(&self.name) => format!("{:#x}", ret),
+            (Some(ret), None) => {
+                let mut text = match self.ret_fd {
+                    Some(ref resource) => format!("{}<{}>", ret, resource),
+                    None => ret.to_string(),
+                };
+                for value in self.returned.values() {
+                    text.push_str(&format!(" ({})", value));
+                }
+                text
+            },
+            (None, _) => "?".to_string(),
+        }
+    }
+}
+
+
+impl fmt::Display for Syscall {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} = {}", self.call(), self.result())
+    }
+}
+
+
+/// `is_false()` is used to skip serializing flags that are not set.
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+
+/// `serialize_duration()` serializes a duration as a number of nanoseconds.
+fn serialize_duration<S: Serializer>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serializer.serialize_u64(duration.as_nanos() as u64),
+        None => serializer.serialize_none(),
+    }
+}
+
+
+/// `serialize_base64()` serializes raw bytes as a base64 string.
+fn serialize_base64<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&base64::encode(data))
+}
+
+
+/// `escape_bytes()` renders raw bytes read from the tracee as a printable string,
+/// escaping control characters and non-ASCII bytes in octal like a C string literal,
+/// or in hex if requested.
+pub fn escape_bytes(bytes: &[u8], hex: HexStyle) -> String {
+    let all_hex = match hex {
+        HexStyle::Never => false,
+        HexStyle::NonAscii => bytes.iter().any(|&byte| !matches!(byte, b'\t'..=b'\r' | 0x20..=0x7e)),
+        HexStyle::All => true,
+    };
+    if all_hex {
+        return bytes.iter().map(|byte| format!("\\x{:02x}", byte)).collect();
+    }
+
+    let mut escaped = String::with_capacity(bytes.len());
+    for (idx, byte) in bytes.iter().enumerate() {
+        match *byte {
+            b'"' => escaped.push_str("\\\""),
+            b'\\' => escaped.push_str("\\\\"),
+            b'\t' => escaped.push_str("\\t"),
+            b'\n' => escaped.push_str("\\n"),
+            b'\r' => escaped.push_str("\\r"),
+            0x0b => escaped.push_str("\\v"),
+            0x0c => escaped.push_str("\\f"),
+            0x20..=0x7e => escaped.push(*byte as char),
+            _ => {
+
+                // use the shortest octal escape, unless the next character is an octal digit
+                let next_is_octal = match bytes.get(idx + 1) {
+                    Some(next) => (b'0'..=b'7').contains(next),
+                    None => false,
+                };
+                if next_is_octal {
+                    escaped.push_str(&format!("\\{:03o}", byte));
+                } else {
+                    escaped.push_str(&format!("\\{:o}", byte));
+                }
+            }
         }
     }
+    escaped
 }
 
+
+/// `errno_name()` decodes a raw syscall return value into the name of the errno
+/// it represents, if any. Only values within [-MAX_ERRNO, -1] are treated as errors.
+pub fn errno_name(ret: i64) -> Option<String> {
+    if ret >= 0 || ret < -MAX_ERRNO {
+        return None;
+    }
+
+    // kernel-internal restart codes are never seen by userspace, but are visible to tracers
+    let errno = -ret;
+    let name = match errno {
+        512 => "ERESTARTSYS".to_string(),
+        513 => "ERESTARTNOINTR".to_string(),
+        514 => "ERESTARTNOHAND".to_string(),
+        515 => "ENOIOCTLCMD".to_string(),
+        516 => "ERESTART_RESTARTBLOCK".to_string(),
+        _ => match Errno::from_i32(errno as i32) {
+            Errno::UnknownErrno => format!("errno{}", errno),
+            e => format!("{:?}", e),
+        }
+    };
+    Some(name)
+}
+
+
+/// `errno_desc()` returns a human-readable description for a positive errno value,
+/// using the same wording as strace, which defers to libc for all but the kernel-internal codes.
+fn errno_desc(errno: i64) -> String {
+    match errno {
+        512 => "To be restarted if SA_RESTART is set".to_string(),
+        513 => "To be restarted".to_string(),
+        514 => "To be restarted if no handler".to_string(),
+        515 => "Unknown ioctl".to_string(),
+        516 => "Interrupted by signal".to_string(),
+        _ => unsafe {
+            CStr::from_ptr(libc::strerror(errno as c_int)).to_string_lossy().into_owned()
+        },
+    }
+}
+
+
+/// `is_restart()` checks if a positive errno value is one of the kernel-internal
+/// restart codes, which userspace never sees as the syscall is restarted instead.
+fn is_restart(errno: i64) -> bool {
+    errno == 512 || errno == 513 || errno == 514 || errno == 516
+}
+
+
+/// SyscallManager resolves syscall numbers into Syscalls through the table for
+/// the architecture they were made through, or a HashMap that stores syscall num
+/// and name mappings parsed from a header for the native architecture if one was
+/// given, selects which of them are traced, and streams every completed Event out
+/// to the configured sink. If requested, it also keeps a table of the open file
+/// descriptors of each process, keyed by thread group, to annotate syscalls with.
+pub struct SyscallManager {
+    sink: Box<dyn EventSink>,
+    filter: Filter,
+    fd_tables: Option<HashMap<i32, FdTable>>,
+    pub _syscall_table: Option<SyscallTable>
+}
+
+
 impl SyscallManager {
 
-    pub fn new() -> Self {
-        Self::default()
+    pub fn new(sink: Box<dyn EventSink>, filter: Filter, header: Option<&str>, track_fds: bool) -> Self {
+        let syscall_table = header.map(|path| {
+            info!("Parsing syscall table from {}", path);
+            SyscallManager::_parse_syscall_table(path).expect("cannot parse syscall table.")
+        });
+        if syscall_table.is_none() {
+            info!("Using built-in syscall table for {}", Arch::native().name());
+        }
+
+        Self {
+            sink,
+            filter,
+            fd_tables: if track_fds { Some(HashMap::new()) } else { None },
+            _syscall_table: syscall_table
+        }
     }
 
     /// `_parse_syscall_table()` is a helper method that parses a "syscall table"
-    /// and instantiates a HashMap that stores the syscall num as a key and the name
-    /// as the value.
+    /// such as unistd_64.h and instantiates a HashMap that stores the syscall num
+    /// as a key and the name as the value.
     #[inline]
-    fn _parse_syscall_table() -> io::Result<SyscallTable> {
+    fn _parse_syscall_table(path: &str) -> io::Result<SyscallTable> {
 
         // read unistd.h for macro definitions
-        let mut tbl_file = File::open(SYSCALL_TABLE)?;
+        let mut tbl_file = File::open(path)?;
         let mut contents = String::new();
         tbl_file.read_to_string(&mut contents)?;
 
@@ -81,7 +587,7 @@ impl SyscallManager {
         }
 
         // find matches and store as 2-ary tuple in vector
-        let matches: Vec<(u64, String)> = RE.captures_iter(&contents.as_str()).filter_map(|cap| {
+        let matches: Vec<(u64, String)> = RE.captures_iter(contents.as_str()).filter_map(|cap| {
             let groups = (cap.get(2), cap.get(1));
             match groups {
                 (Some(ref num), Some(ref name)) => {
@@ -97,43 +603,295 @@ impl SyscallManager {
     }
 
 
-    /// `add_syscall()` finds a corresponding syscall name from
-    /// a parsed syscall table and instantiates and stores a new Syscall.
-    pub fn add_syscall(&mut self, syscall_num: u64, args: Vec<u64>) -> () {
+    /// `syscall_name()` resolves a syscall number made through the given architecture
+    /// into its name, if known.
+    fn syscall_name(&self, arch: Arch, syscall_num: u64) -> Option<&str> {
+        match self._syscall_table {
+            Some(ref table) if arch == Arch::native() => table.get(&syscall_num).map(String::as_str),
+            _ => arch.syscall_name(syscall_num),
+        }
+    }
 
-        // retrieve syscall name from HashMap by syscall_num key
-        let syscall_name = match self._syscall_table.get(&syscall_num) {
-            Some(s) => s,
-            None => {
-                panic!("unable to determine corresponding syscall for number {}", syscall_num);
-            }
+
+    /// `name()` returns the name of a syscall, or a name derived from its number
+    /// such as `syscall_0x1c3` if it is missing from the table.
+    pub fn name(&self, arch: Arch, syscall_num: u64) -> String {
+        match self.syscall_name(arch, syscall_num) {
+            Some(name) => name.to_string(),
+            None => format!("syscall_{:#x}", syscall_num),
+        }
+    }
+
+
+    /// `nargs()` returns the number of registers the syscall with the given number
+    /// takes its arguments in, falling back to the maximum if it cannot be resolved.
+    pub fn nargs(&self, arch: Arch, syscall_num: u64) -> usize {
+        match self.syscall_name(arch, syscall_num) {
+            Some(name) => sysent::nargs(name, arch),
+            None => sysent::MAX_ARGS,
+        }
+    }
+
+
+    /// `traced()` checks if the syscall with the given number is selected by the filter,
+    /// such that syscalls that are not can be skipped before they are decoded.
+    pub fn traced(&self, arch: Arch, syscall_num: u64) -> bool {
+        self.filter.traced(&self.name(arch, syscall_num))
+    }
+
+
+    /// `selected()` checks if a traced syscall refers to the paths and descriptors the
+    /// filter restricts output to, given the paths among its arguments. Descriptors are
+    /// matched against paths through the resources they refer to.
+    pub fn selected(&self, syscall: &Syscall, paths: &[String]) -> bool {
+        let fds: Vec<(i32, Option<&str>)> = syscall.args
+            .iter()
+            .enumerate()
+            .filter(|&(idx, &arg)| match args::arg_type(&syscall.name, idx) {
+                ArgType::Fd => true,
+                ArgType::DirFd => arg as i32 != libc::AT_FDCWD,
+                _ => false,
+            })
+            .map(|(idx, &arg)| (arg as i32, syscall.fd(idx)))
+            .collect();
+        self.filter.selects(paths, &fds)
+    }
+
+
+    /// `new_syscall()` finds a corresponding syscall name from
+    /// the syscall table and instantiates a new Syscall, which
+    /// remains unfinished until its return value is set on SYS_EXIT.
+    /// Arguments are taken from the registers the syscall was made with.
+    pub fn new_syscall(&self, pid: i32, tid: i32, arch: Arch, syscall_num: u64, regs: Vec<u64>) -> Syscall {
+
+        // retrieve syscall name from the table by syscall_num key
+        let unknown = self.syscall_name(arch, syscall_num).is_none();
+        if unknown {
+            warn!("Unable to determine corresponding syscall for number {}", syscall_num);
+        }
+
+        // 64-bit arguments split across a pair of registers are joined back together
+        let name = self.name(arch, syscall_num);
+        let args = sysent::join_args(&name, arch, &regs);
+        let decoded = if unknown {
+            BTreeMap::new()
+        } else {
+            args::decode(&name, arch, &args)
         };
 
-        // initialize Syscall definition and store
-        let syscall = Syscall {
+        // resolve descriptor arguments while they are still open, as the syscall may close them
+        let mut fds = BTreeMap::new();
+        if let Some(table) = self.fd_tables.as_ref().and_then(|tables| tables.get(&pid)) {
+            for (idx, &arg) in args.iter().enumerate() {
+                if let ArgType::Fd | ArgType::DirFd = args::arg_type(&name, idx) {
+                    if let Some(resource) = table.get(arg as i32) {
+                        fds.insert(idx, resource.to_string());
+                    }
+                }
+            }
+        }
+
+        Syscall {
+            pid,
+            tid,
             number: syscall_num,
-            name: syscall_name.to_string(),
-            args: args
+            name,
+            arch,
+            unknown,
+            args,
+            strings: BTreeMap::new(),
+            decoded,
+            structs: BTreeMap::new(),
+            returned: BTreeMap::new(),
+            buffers: BTreeMap::new(),
+            fds,
+            ret: None,
+            ret_fd: None,
+            errno: None,
+            unfinished: true,
+            timestamp_ns: event::timestamp_ns(),
+            duration: None,
+            entry_regs: None,
+            exit_regs: None,
+            entered: Instant::now(),
+            suppressed: false,
+        }
+    }
+
+
+    /// `tracks_fds()` checks if the descriptors of each process are tracked, in which case
+    /// every syscall is followed through to SYS_EXIT, even if it is not traced.
+    pub fn tracks_fds(&self) -> bool {
+        self.fd_tables.is_some()
+    }
+
+
+    /// `seed_fds()` starts tracking the descriptors of a new process from those it has
+    /// open, such as those inherited from its parent.
+    pub fn seed_fds(&mut self, pid: i32) {
+        if let Some(ref mut tables) = self.fd_tables {
+            let table = FdTable::from_proc(pid).unwrap_or_else(|e| {
+                debug!("Unable to read descriptors of process {}: {:?}", pid, e);
+                FdTable::default()
+            });
+            tables.insert(pid, table);
+        }
+    }
+
+
+    /// `drop_fds()` stops tracking the descriptors of a process once it has exited.
+    pub fn drop_fds(&mut self, pid: i32) {
+        if let Some(ref mut tables) = self.fd_tables {
+            tables.remove(&pid);
+        }
+    }
+
+
+    /// `update_fds()` applies the effect of a syscall that reached SYS_EXIT on the
+    /// descriptors of its process, and annotates any descriptor it returned.
+    pub fn update_fds(&mut self, syscall: &mut Syscall) {
+        let table = match self.fd_tables.as_mut().and_then(|tables| tables.get_mut(&syscall.pid)) {
+            Some(table) => table,
+            None => return,
         };
-        self.syscalls.push(syscall);
+        if let Some(resource) = table.update(syscall).and_then(|fd| table.get(fd)) {
+            syscall.set_ret_fd(resource);
+        }
+    }
+
+
+    /// `emit()` writes out a completed event, such as a finished syscall or a signal.
+    /// Syscalls that were only followed for their effect on descriptors are dropped.
+    pub fn emit(&mut self, event: Event) -> io::Result<()> {
+        if let Event::Syscall(ref syscall) = event {
+            if syscall.suppressed {
+                return Ok(());
+            }
+        }
+        self.sink.write_event(event)
     }
 
 
-    pub fn to_json(&mut self) -> Result<String> {
-        serde_json::to_string(&self)
+    /// `finish()` completes the output once tracing is done.
+    pub fn finish(&mut self) -> io::Result<()> {
+        self.sink.finish()
     }
 }
 
 
-impl fmt::Display for SyscallManager {
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 
-        // collect syscalls into formattable string
-        let syscalls: Vec<String> = self.syscalls
-            .iter()
-            .map(|x| format!("{}({:?})", x.name, x.args))
-            .collect();
-        write!(f, "{:?}", syscalls)
+    /// Discards every event, as syscalls are only rendered here.
+    struct NullSink;
+
+    impl EventSink for NullSink {
+        fn write_event(&mut self, _event: Event) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+
+    /// `syscall()` builds a syscall as seen at SYS_ENTER of an x86_64 task, with its
+    /// path argument read back as `path`.
+    fn syscall(num: u64, args: &[u64], path: (usize, &str)) -> Syscall {
+        let manager = SyscallManager::new(Box::new(NullSink), Filter::default(), None, false);
+        let mut syscall = manager.new_syscall(1, 1, Arch::X86_64, num, args.to_vec());
+        syscall.set_string(path.0, path.1.as_bytes(), false, HexStyle::Never);
+        syscall
+    }
+
+
+    #[test]
+    fn open_without_mode() {
+        let open = syscall(257, &[-100i64 as u64, 0x7ffd_5d1e_0a10, 0x80000, 0o777], (1, "/etc/hostname"));
+        assert_eq!(open.call(), r#"openat(AT_FDCWD, "/etc/hostname", O_RDONLY|O_CLOEXEC)"#);
+        let open = syscall(2, &[0x7ffd_5d1e_0a10, 0, 0o777], (0, "/etc/hostname"));
+        assert_eq!(open.call(), r#"open("/etc/hostname", O_RDONLY)"#);
+    }
+
+
+    #[test]
+    fn open_with_mode() {
+        let creat = (libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC) as u64;
+        let open = syscall(257, &[-100i64 as u64, 0x7ffd_5d1e_0a10, creat, 0o644], (1, "/tmp/out"));
+        assert_eq!(open.call(), r#"openat(AT_FDCWD, "/tmp/out", O_WRONLY|O_CREAT|O_TRUNC, 0644)"#);
+
+        // O_TMPFILE includes O_DIRECTORY, which alone does not take a mode
+        let tmpfile = (libc::O_RDWR | libc::O_TMPFILE) as u64;
+        let open = syscall(2, &[0x7ffd_5d1e_0a10, tmpfile, 0o600], (0, "/tmp"));
+        assert_eq!(open.call(), r#"open("/tmp", O_RDWR|O_TMPFILE, 0600)"#);
+        let directory = (libc::O_RDONLY | libc::O_DIRECTORY) as u64;
+        let open = syscall(2, &[0x7ffd_5d1e_0a10, directory, 0o600], (0, "/tmp"));
+        assert_eq!(open.call(), r#"open("/tmp", O_RDONLY|O_DIRECTORY)"#);
+    }
+
+
+    #[test]
+    fn i386_arguments() {
+        let manager = SyscallManager::new(Box::new(NullSink), Filter::default(), None, false);
+
+        // #90 takes a single pointer to the arguments of mmap
+        assert_eq!(manager.name(Arch::I386, 90), "old_mmap");
+        assert_eq!(manager.nargs(Arch::I386, 90), 1);
+        let mmap = manager.new_syscall(1, 1, Arch::I386, 90, vec![0xffd1_2340]);
+        assert_eq!(mmap.call(), "old_mmap(0xffd12340)");
+
+        // file offsets are split across a pair of registers, low half first
+        assert_eq!(manager.nargs(Arch::I386, 180), 5);
+        assert_eq!(manager.nargs(Arch::X86_64, 17), 4);
+        let pread = manager.new_syscall(1, 1, Arch::I386, 180, vec![3, 0x0804_a000, 4096, 0x1000, 0x1]);
+        assert_eq!(pread.call(), "pread64(3, 0x804a000, 4096, 4294971392)");
+
+        // some of which are aligned to an even register
+        assert_eq!(manager.nargs(Arch::I386, 324), 6);
+        let fallocate = manager.new_syscall(1, 1, Arch::I386, 324, vec![3, 0, 0x1000, 0, 0, 0x2]);
+        assert_eq!(fallocate.call(), "fallocate(3, 0, 4096, 8589934592)");
+        assert_eq!(manager.nargs(Arch::I386, 250), 5);
+        let fadvise = manager.new_syscall(1, 1, Arch::I386, 250, vec![3, 0xffff_f000, 0x7fff_ffff, 0, 2]);
+        assert_eq!(fadvise.call(), "fadvise64(3, 9223372036854771712, 0, 2)");
+    }
+
+
+    #[test]
+    fn escape_bytes_in_each_style() {
+        let bytes = b"GET /\r\n\0\x017\"\\";
+        assert_eq!(escape_bytes(bytes, HexStyle::Never), r#"GET /\r\n\0\0017\"\\"#);
+        assert_eq!(escape_bytes(bytes, HexStyle::NonAscii),
+                   r#"\x47\x45\x54\x20\x2f\x0d\x0a\x00\x01\x37\x22\x5c"#);
+        assert_eq!(escape_bytes(b"hi\tthere\n", HexStyle::NonAscii), r#"hi\tthere\n"#);
+        assert_eq!(escape_bytes(b"hi\n", HexStyle::All), r#"\x68\x69\x0a"#);
+    }
+
+
+    #[test]
+    fn hexdump_lines() {
+        let buffer = Buffer::new(b"0123456789abcdef".to_vec(), 16, 32, HexStyle::Never, true);
+        assert_eq!(buffer.hexdump(), vec![
+            " | 00000  30 31 32 33 34 35 36 37  38 39 61 62 63 64 65 66  0123456789abcdef |",
+        ]);
+
+        let buffer = Buffer::new(b"0123456789abcdef\n".to_vec(), 17, 32, HexStyle::Never, true);
+        assert_eq!(buffer.hexdump(), vec![
+            " | 00000  30 31 32 33 34 35 36 37  38 39 61 62 63 64 65 66  0123456789abcdef |",
+            " | 00010  0a                                                .                |",
+        ]);
+        assert!(Buffer::new(Vec::new(), 0, 32, HexStyle::Never, true).hexdump().is_empty());
+    }
+
+
+    #[test]
+    fn buffers_as_base64() {
+        let json = |data: &[u8]| serde_json::to_string(&Buffer::new(data.to_vec(), data.len(), 32, HexStyle::Never, false)).unwrap();
+        assert_eq!(json(b"a"), r#"{"base64":"YQ==","truncated":false}"#);
+        assert_eq!(json(b"ab"), r#"{"base64":"YWI=","truncated":false}"#);
+        assert_eq!(json(b"abc"), r#"{"base64":"YWJj","truncated":false}"#);
+
+        // only what was read is encoded, with the rest of the transfer marked as missing
+        let buffer = Buffer::new(b"abcd".to_vec(), 4096, 4, HexStyle::Never, false);
+        assert_eq!(serde_json::to_string(&buffer).unwrap(), r#"{"base64":"YWJjZA==","truncated":true}"#);
+        assert_eq!(buffer.to_string(), r#""abcd"..."#);
     }
 }
@@ -0,0 +1,818 @@
+//! sysent.rs
+//!
+//!     Static syscall metadata, keyed by syscall name.
+//!
+//!     Syscall numbers differ between architectures, but the names
+//!     and calling signatures mostly do not, so entries here are looked
+//!     up by name once the number has been resolved against a table.
+//!     Where they do, such as for 64-bit arguments passed in a pair of
+//!     registers on i386, the architecture is taken into account.
+
+use std::collections::HashMap;
+
+use arch::Arch;
+
+
+/// Describes the calling signature of a single syscall.
+pub struct Sysent {
+    pub name: &'static str,
+    pub nargs: usize,
+}
+
+
+/// Arguments that point to a NUL-terminated string in tracee memory. Paths
+/// are always read in full, while other strings are subject to the
+/// user-configured string limit.
+#[derive(Clone, Copy, PartialEq)]
+pub enum StrArg {
+    Path,
+    Str,
+}
+
+
+bitflags! {
+    /// Classes that syscalls are grouped into for filtering, as in strace. A syscall
+    /// can belong to several classes, such as `openat` which takes both a path and
+    /// a file descriptor.
+    pub struct Class: u32 {
+        const FILE    = 1 << 0;
+        const PROCESS = 1 << 1;
+        const NETWORK = 1 << 2;
+        const SIGNAL  = 1 << 3;
+        const IPC     = 1 << 4;
+        const MEMORY  = 1 << 5;
+        const DESC    = 1 << 6;
+    }
+}
+
+
+/// maximum length of a path read from the tracee, matching PATH_MAX
+pub const PATH_MAX: usize = 4096;
+
+
+/// maximum number of arguments a syscall can take in the kernel ABI
+pub const MAX_ARGS: usize = 6;
+
+
+lazy_static! {
+    static ref SYSENT_MAP: HashMap<&'static str, &'static Sysent> = SYSENT
+        .iter()
+        .map(|ent| (ent.name, ent))
+        .collect();
+
+    static ref STRING_ARGS_MAP: HashMap<&'static str, &'static [(usize, StrArg)]> = STRING_ARGS
+        .iter()
+        .cloned()
+        .collect();
+
+    static ref CLASSES_MAP: HashMap<&'static str, Class> = {
+        let mut map = HashMap::new();
+        for &(class, names) in CLASSES {
+            for name in names {
+                *map.entry(*name).or_insert_with(Class::empty) |= class;
+            }
+        }
+        map
+    };
+}
+
+
+/// `lookup()` returns the metadata for a syscall by name, if known.
+pub fn lookup(name: &str) -> Option<&'static Sysent> {
+    SYSENT_MAP.get(name).cloned()
+}
+
+
+/// `nargs()` returns the number of registers a syscall takes its arguments in for
+/// the given architecture, defaulting to the full `MAX_ARGS` if the syscall is not
+/// known. This exceeds its true arity where 64-bit arguments are split in two.
+pub fn nargs(name: &str, arch: Arch) -> usize {
+    lookup(name).map_or(MAX_ARGS, |ent| ent.nargs + split_args(name, arch).len())
+}
+
+
+/// `split_args()` returns the registers holding the lower half of each 64-bit argument
+/// a syscall takes in a pair of registers for the given architecture, in order, with
+/// the upper half in the register that follows.
+pub fn split_args(name: &str, arch: Arch) -> &'static [usize] {
+    let split: &[(&str, &'static [usize])] = match arch {
+        Arch::I386 => SPLIT_ARGS_I386,
+        _ => &[],
+    };
+    split.iter()
+         .find(|&&(split_name, _)| split_name == name)
+         .map_or(&[], |&(_, regs)| regs)
+}
+
+
+/// `join_args()` combines the registers a syscall was made with into its arguments,
+/// joining those split into a pair of registers for the given architecture.
+pub fn join_args(name: &str, arch: Arch, regs: &[u64]) -> Vec<u64> {
+    let split = split_args(name, arch);
+    let mut args = Vec::with_capacity(regs.len());
+    let mut idx = 0;
+    while idx < regs.len() {
+        match regs.get(idx + 1) {
+            Some(&high) if split.contains(&idx) => {
+                args.push(regs[idx] & 0xffff_ffff | high << 32);
+                idx += 2;
+            },
+            _ => {
+                args.push(regs[idx]);
+                idx += 1;
+            },
+        }
+    }
+    args
+}
+
+
+/// `string_args()` returns the indices of arguments for a syscall that should be
+/// decoded as strings from tracee memory, alongside what kind of string they are.
+pub fn string_args(name: &str) -> &'static [(usize, StrArg)] {
+    STRING_ARGS_MAP.get(name).cloned().unwrap_or(&[])
+}
+
+
+/// `classes()` returns the classes a syscall belongs to, which are empty if the
+/// syscall falls under none of them.
+pub fn classes(name: &str) -> Class {
+    CLASSES_MAP.get(name).cloned().unwrap_or_else(Class::empty)
+}
+
+
+/// `returns_addr()` checks if a syscall returns an address in tracee memory
+/// on success, which is rendered in hex rather than as a number.
+pub fn returns_addr(name: &str) -> bool {
+    ADDR_RETURNS.contains(&name)
+}
+
+
+static ADDR_RETURNS: &[&str] = &[
+    "brk",
+    "mmap",
+    "mmap2",
+    "mremap",
+    "old_mmap",
+    "shmat",
+];
+
+
+/// 64-bit arguments passed in a pair of registers by i386 tasks, of which some are
+/// aligned to an even register in the kernel ABI and others are not.
+static SPLIT_ARGS_I386: &[(&str, &[usize])] = &[
+    ("fadvise64",       &[1]),
+    ("fadvise64_64",    &[1, 3]),
+    ("fallocate",       &[2, 4]),
+    ("ftruncate64",     &[1]),
+    ("pread64",         &[3]),
+    ("pwrite64",        &[3]),
+    ("readahead",       &[1]),
+    ("sync_file_range", &[1, 3]),
+    ("truncate64",      &[1]),
+];
+
+
+static CLASSES: &[(Class, &[&str])] = &[
+    (Class::FILE, &[
+        "access", "acct", "chdir", "chmod", "chown", "chown32", "chroot", "creat", "execve",
+        "execveat", "faccessat", "faccessat2", "fanotify_mark", "fchmodat", "fchmodat2", "fchownat",
+        "fspick", "fstatat64", "futimesat", "getxattr", "getxattrat", "inotify_add_watch", "lchown",
+        "lchown32", "lgetxattr", "link", "linkat", "listxattr", "listxattrat", "llistxattr",
+        "lremovexattr", "lsetxattr", "lstat", "lstat64", "mkdir", "mkdirat", "mknod", "mknodat",
+        "mount", "move_mount", "name_to_handle_at", "newfstatat", "oldlstat", "oldstat", "open",
+        "open_tree", "open_tree_attr", "openat", "openat2", "pivot_root", "quotactl", "readlink",
+        "readlinkat", "removexattr", "removexattrat", "rename", "renameat", "renameat2", "rmdir",
+        "setxattr", "setxattrat", "stat", "stat64", "statfs", "statfs64", "statx", "swapoff",
+        "swapon", "symlink", "symlinkat", "truncate", "truncate64", "umount", "umount2", "unlink",
+        "unlinkat", "uselib", "utime", "utimensat", "utimes",
+    ]),
+    (Class::PROCESS, &[
+        "clone", "clone3", "execve", "execveat", "exit", "exit_group", "fork", "kill",
+        "pidfd_getfd", "pidfd_open", "pidfd_send_signal", "rt_sigqueueinfo", "rt_tgsigqueueinfo",
+        "tgkill", "tkill", "unshare", "vfork", "wait4", "waitid", "waitpid",
+    ]),
+    (Class::NETWORK, &[
+        "accept", "accept4", "bind", "connect", "getpeername", "getsockname", "getsockopt",
+        "listen", "recvfrom", "recvmmsg", "recvmmsg_time64", "recvmsg", "sendmmsg", "sendmsg",
+        "sendto", "setsockopt", "shutdown", "socket", "socketcall", "socketpair",
+    ]),
+    (Class::SIGNAL, &[
+        "kill", "pause", "pidfd_send_signal", "rt_sigaction", "rt_sigpending", "rt_sigprocmask",
+        "rt_sigqueueinfo", "rt_sigreturn", "rt_sigsuspend", "rt_sigtimedwait",
+        "rt_sigtimedwait_time64", "rt_tgsigqueueinfo", "sgetmask", "sigaction", "sigaltstack",
+        "signal", "signalfd", "signalfd4", "sigpending", "sigprocmask", "sigreturn", "sigsuspend",
+        "ssetmask", "tgkill", "tkill",
+    ]),
+    (Class::IPC, &[
+        "ipc", "msgctl", "msgget", "msgrcv", "msgsnd", "semctl", "semget", "semop", "semtimedop",
+        "semtimedop_time64", "shmat", "shmctl", "shmdt", "shmget",
+    ]),
+    (Class::MEMORY, &[
+        "brk", "get_mempolicy", "madvise", "map_shadow_stack", "mbind", "migrate_pages", "mincore",
+        "mlock", "mlock2", "mlockall", "mmap", "mmap2", "move_pages", "mprotect", "mremap", "mseal",
+        "msync", "munlock", "munlockall", "munmap", "old_mmap", "pkey_mprotect",
+        "process_madvise", "remap_file_pages", "set_mempolicy", "set_mempolicy_home_node", "shmat",
+        "shmdt",
+    ]),
+    (Class::DESC, &[
+        "_llseek", "_newselect", "accept", "accept4", "bind", "bpf", "cachestat", "close",
+        "close_range", "connect", "copy_file_range", "creat", "dup", "dup2", "dup3", "epoll_create",
+        "epoll_create1", "epoll_ctl", "epoll_ctl_old", "epoll_pwait", "epoll_pwait2", "epoll_wait",
+        "epoll_wait_old", "eventfd", "eventfd2", "execveat", "faccessat", "faccessat2", "fadvise64",
+        "fadvise64_64", "fallocate", "fanotify_init", "fanotify_mark", "fchdir", "fchmod",
+        "fchmodat", "fchmodat2", "fchown", "fchown32", "fchownat", "fcntl", "fcntl64", "fdatasync",
+        "fgetxattr", "flistxattr", "flock", "fremovexattr", "fsconfig", "fsetxattr", "fsmount",
+        "fsopen", "fspick", "fstat", "fstat64", "fstatat64", "fstatfs", "fstatfs64", "fsync",
+        "ftruncate", "ftruncate64", "futimesat", "getdents", "getdents64", "getpeername",
+        "getsockname", "getsockopt", "getxattrat", "inotify_add_watch", "inotify_init",
+        "inotify_init1", "inotify_rm_watch", "io_uring_enter", "io_uring_register",
+        "io_uring_setup", "ioctl", "landlock_add_rule", "landlock_create_ruleset",
+        "landlock_restrict_self", "linkat", "listen", "listxattrat", "lseek", "memfd_create",
+        "memfd_secret", "mkdirat", "mknodat", "mmap", "mmap2", "mount_setattr", "move_mount",
+        "mq_getsetattr", "mq_notify", "mq_open", "mq_timedreceive", "mq_timedreceive_time64",
+        "mq_timedsend", "mq_timedsend_time64", "name_to_handle_at", "newfstatat", "old_mmap",
+        "oldfstat", "open", "open_by_handle_at", "open_tree", "open_tree_attr", "openat", "openat2",
+        "perf_event_open", "pidfd_getfd", "pidfd_open", "pidfd_send_signal", "pipe", "pipe2",
+        "poll", "ppoll", "ppoll_time64", "pread64", "preadv", "preadv2", "process_madvise",
+        "process_mrelease", "pselect6", "pselect6_time64", "pwrite64", "pwritev", "pwritev2",
+        "quotactl_fd", "read", "readahead", "readlinkat", "readv", "recvfrom", "recvmmsg",
+        "recvmmsg_time64", "recvmsg", "removexattrat", "renameat", "renameat2", "select",
+        "sendfile", "sendfile64", "sendmmsg", "sendmsg", "sendto", "setns", "setsockopt",
+        "setxattrat", "shutdown", "signalfd", "signalfd4", "socket", "socketpair", "splice",
+        "statx", "symlinkat", "sync_file_range", "sync_file_range2", "syncfs", "tee",
+        "timerfd_create", "timerfd_gettime", "timerfd_gettime64", "timerfd_settime",
+        "timerfd_settime64", "unlinkat", "userfaultfd", "utimensat", "utimensat_time64", "vmsplice",
+        "write", "writev",
+    ]),
+];
+
+
+static STRING_ARGS: &[(&str, &[(usize, StrArg)])] = &[
+    ("access",            &[(0, StrArg::Path)]),
+    ("acct",              &[(0, StrArg::Path)]),
+    ("add_key",           &[(0, StrArg::Str), (1, StrArg::Str)]),
+    ("chdir",             &[(0, StrArg::Path)]),
+    ("chmod",             &[(0, StrArg::Path)]),
+    ("chown",             &[(0, StrArg::Path)]),
+    ("chown32",           &[(0, StrArg::Path)]),
+    ("chroot",            &[(0, StrArg::Path)]),
+    ("creat",             &[(0, StrArg::Path)]),
+    ("delete_module",     &[(0, StrArg::Str)]),
+    ("execve",            &[(0, StrArg::Path)]),
+    ("execveat",          &[(1, StrArg::Path)]),
+    ("faccessat",         &[(1, StrArg::Path)]),
+    ("faccessat2",        &[(1, StrArg::Path)]),
+    ("fanotify_mark",     &[(4, StrArg::Path)]),
+    ("fchmodat",          &[(1, StrArg::Path)]),
+    ("fchmodat2",         &[(1, StrArg::Path)]),
+    ("fchownat",          &[(1, StrArg::Path)]),
+    ("fgetxattr",         &[(1, StrArg::Str)]),
+    ("finit_module",      &[(1, StrArg::Str)]),
+    ("fremovexattr",      &[(1, StrArg::Str)]),
+    ("fsconfig",          &[(2, StrArg::Str)]),
+    ("fsetxattr",         &[(1, StrArg::Str)]),
+    ("fsopen",            &[(0, StrArg::Str)]),
+    ("fspick",            &[(1, StrArg::Path)]),
+    ("fstatat64",         &[(1, StrArg::Path)]),
+    ("futimesat",         &[(1, StrArg::Path)]),
+    ("getxattr",          &[(0, StrArg::Path), (1, StrArg::Str)]),
+    ("init_module",       &[(2, StrArg::Str)]),
+    ("inotify_add_watch", &[(1, StrArg::Path)]),
+    ("lchown",            &[(0, StrArg::Path)]),
+    ("lchown32",          &[(0, StrArg::Path)]),
+    ("lgetxattr",         &[(0, StrArg::Path), (1, StrArg::Str)]),
+    ("link",              &[(0, StrArg::Path), (1, StrArg::Path)]),
+    ("linkat",            &[(1, StrArg::Path), (3, StrArg::Path)]),
+    ("listxattr",         &[(0, StrArg::Path)]),
+    ("llistxattr",        &[(0, StrArg::Path)]),
+    ("lremovexattr",      &[(0, StrArg::Path), (1, StrArg::Str)]),
+    ("lsetxattr",         &[(0, StrArg::Path), (1, StrArg::Str)]),
+    ("lstat",             &[(0, StrArg::Path)]),
+    ("lstat64",           &[(0, StrArg::Path)]),
+    ("memfd_create",      &[(0, StrArg::Str)]),
+    ("mkdir",             &[(0, StrArg::Path)]),
+    ("mkdirat",           &[(1, StrArg::Path)]),
+    ("mknod",             &[(0, StrArg::Path)]),
+    ("mknodat",           &[(1, StrArg::Path)]),
+    ("mount",             &[(0, StrArg::Path), (1, StrArg::Path), (2, StrArg::Str)]),
+    ("move_mount",        &[(1, StrArg::Path), (3, StrArg::Path)]),
+    ("mq_open",           &[(0, StrArg::Str)]),
+    ("mq_unlink",         &[(0, StrArg::Str)]),
+    ("name_to_handle_at", &[(1, StrArg::Path)]),
+    ("newfstatat",        &[(1, StrArg::Path)]),
+    ("oldlstat",          &[(0, StrArg::Path)]),
+    ("oldstat",           &[(0, StrArg::Path)]),
+    ("open",              &[(0, StrArg::Path)]),
+    ("open_tree",         &[(1, StrArg::Path)]),
+    ("openat",            &[(1, StrArg::Path)]),
+    ("openat2",           &[(1, StrArg::Path)]),
+    ("pivot_root",        &[(0, StrArg::Path), (1, StrArg::Path)]),
+    ("quotactl",          &[(1, StrArg::Path)]),
+    ("readlink",          &[(0, StrArg::Path)]),
+    ("readlinkat",        &[(1, StrArg::Path)]),
+    ("removexattr",       &[(0, StrArg::Path), (1, StrArg::Str)]),
+    ("rename",            &[(0, StrArg::Path), (1, StrArg::Path)]),
+    ("renameat",          &[(1, StrArg::Path), (3, StrArg::Path)]),
+    ("renameat2",         &[(1, StrArg::Path), (3, StrArg::Path)]),
+    ("request_key",       &[(0, StrArg::Str), (1, StrArg::Str), (2, StrArg::Str)]),
+    ("rmdir",             &[(0, StrArg::Path)]),
+    ("setdomainname",     &[(0, StrArg::Str)]),
+    ("sethostname",       &[(0, StrArg::Str)]),
+    ("setxattr",          &[(0, StrArg::Path), (1, StrArg::Str)]),
+    ("stat",              &[(0, StrArg::Path)]),
+    ("stat64",            &[(0, StrArg::Path)]),
+    ("statfs",            &[(0, StrArg::Path)]),
+    ("statfs64",          &[(0, StrArg::Path)]),
+    ("statx",             &[(1, StrArg::Path)]),
+    ("swapoff",           &[(0, StrArg::Path)]),
+    ("swapon",            &[(0, StrArg::Path)]),
+    ("symlink",           &[(0, StrArg::Path), (1, StrArg::Path)]),
+    ("symlinkat",         &[(0, StrArg::Path), (2, StrArg::Path)]),
+    ("truncate",          &[(0, StrArg::Path)]),
+    ("truncate64",        &[(0, StrArg::Path)]),
+    ("umount",            &[(0, StrArg::Path)]),
+    ("umount2",           &[(0, StrArg::Path)]),
+    ("unlink",            &[(0, StrArg::Path)]),
+    ("unlinkat",          &[(1, StrArg::Path)]),
+    ("uselib",            &[(0, StrArg::Path)]),
+    ("utime",             &[(0, StrArg::Path)]),
+    ("utimensat",         &[(1, StrArg::Path)]),
+    ("utimes",            &[(0, StrArg::Path)]),
+];
+
+
+static SYSENT: &[Sysent] = &[
+    Sysent { name: "_llseek",                      nargs: 5 },
+    Sysent { name: "_newselect",                   nargs: 5 },
+    Sysent { name: "_sysctl",                      nargs: 1 },
+    Sysent { name: "accept",                       nargs: 3 },
+    Sysent { name: "accept4",                      nargs: 4 },
+    Sysent { name: "access",                       nargs: 2 },
+    Sysent { name: "acct",                         nargs: 1 },
+    Sysent { name: "add_key",                      nargs: 5 },
+    Sysent { name: "adjtimex",                     nargs: 1 },
+    Sysent { name: "afs_syscall",                  nargs: 0 },
+    Sysent { name: "alarm",                        nargs: 1 },
+    Sysent { name: "arch_prctl",                   nargs: 2 },
+    Sysent { name: "bdflush",                      nargs: 2 },
+    Sysent { name: "bind",                         nargs: 3 },
+    Sysent { name: "bpf",                          nargs: 3 },
+    Sysent { name: "break",                        nargs: 0 },
+    Sysent { name: "brk",                          nargs: 1 },
+    Sysent { name: "cachestat",                    nargs: 4 },
+    Sysent { name: "capget",                       nargs: 2 },
+    Sysent { name: "capset",                       nargs: 2 },
+    Sysent { name: "chdir",                        nargs: 1 },
+    Sysent { name: "chmod",                        nargs: 2 },
+    Sysent { name: "chown",                        nargs: 3 },
+    Sysent { name: "chown32",                      nargs: 3 },
+    Sysent { name: "chroot",                       nargs: 1 },
+    Sysent { name: "clock_adjtime",                nargs: 2 },
+    Sysent { name: "clock_adjtime64",              nargs: 2 },
+    Sysent { name: "clock_getres",                 nargs: 2 },
+    Sysent { name: "clock_getres_time64",          nargs: 2 },
+    Sysent { name: "clock_gettime",                nargs: 2 },
+    Sysent { name: "clock_gettime64",              nargs: 2 },
+    Sysent { name: "clock_nanosleep",              nargs: 4 },
+    Sysent { name: "clock_nanosleep_time64",       nargs: 4 },
+    Sysent { name: "clock_settime",                nargs: 2 },
+    Sysent { name: "clock_settime64",              nargs: 2 },
+    Sysent { name: "clone",                        nargs: 5 },
+    Sysent { name: "clone3",                       nargs: 2 },
+    Sysent { name: "close",                        nargs: 1 },
+    Sysent { name: "close_range",                  nargs: 3 },
+    Sysent { name: "connect",                      nargs: 3 },
+    Sysent { name: "copy_file_range",              nargs: 6 },
+    Sysent { name: "creat",                        nargs: 2 },
+    Sysent { name: "create_module",                nargs: 2 },
+    Sysent { name: "delete_module",                nargs: 2 },
+    Sysent { name: "dup",                          nargs: 1 },
+    Sysent { name: "dup2",                         nargs: 2 },
+    Sysent { name: "dup3",                         nargs: 3 },
+    Sysent { name: "epoll_create",                 nargs: 1 },
+    Sysent { name: "epoll_create1",                nargs: 1 },
+    Sysent { name: "epoll_ctl",                    nargs: 4 },
+    Sysent { name: "epoll_ctl_old",                nargs: 4 },
+    Sysent { name: "epoll_pwait",                  nargs: 6 },
+    Sysent { name: "epoll_pwait2",                 nargs: 6 },
+    Sysent { name: "epoll_wait",                   nargs: 4 },
+    Sysent { name: "epoll_wait_old",               nargs: 4 },
+    Sysent { name: "eventfd",                      nargs: 1 },
+    Sysent { name: "eventfd2",                     nargs: 2 },
+    Sysent { name: "execve",                       nargs: 3 },
+    Sysent { name: "execveat",                     nargs: 5 },
+    Sysent { name: "exit",                         nargs: 1 },
+    Sysent { name: "exit_group",                   nargs: 1 },
+    Sysent { name: "faccessat",                    nargs: 3 },
+    Sysent { name: "faccessat2",                   nargs: 4 },
+    Sysent { name: "fadvise64",                    nargs: 4 },
+    Sysent { name: "fadvise64_64",                 nargs: 4 },
+    Sysent { name: "fallocate",                    nargs: 4 },
+    Sysent { name: "fanotify_init",                nargs: 2 },
+    Sysent { name: "fanotify_mark",                nargs: 5 },
+    Sysent { name: "fchdir",                       nargs: 1 },
+    Sysent { name: "fchmod",                       nargs: 2 },
+    Sysent { name: "fchmodat",                     nargs: 3 },
+    Sysent { name: "fchmodat2",                    nargs: 4 },
+    Sysent { name: "fchown",                       nargs: 3 },
+    Sysent { name: "fchown32",                     nargs: 3 },
+    Sysent { name: "fchownat",                     nargs: 5 },
+    Sysent { name: "fcntl",                        nargs: 3 },
+    Sysent { name: "fcntl64",                      nargs: 3 },
+    Sysent { name: "fdatasync",                    nargs: 1 },
+    Sysent { name: "fgetxattr",                    nargs: 4 },
+    Sysent { name: "finit_module",                 nargs: 3 },
+    Sysent { name: "flistxattr",                   nargs: 3 },
+    Sysent { name: "flock",                        nargs: 2 },
+    Sysent { name: "fork",                         nargs: 0 },
+    Sysent { name: "fremovexattr",                 nargs: 2 },
+    Sysent { name: "fsconfig",                     nargs: 5 },
+    Sysent { name: "fsetxattr",                    nargs: 5 },
+    Sysent { name: "fsmount",                      nargs: 3 },
+    Sysent { name: "fsopen",                       nargs: 2 },
+    Sysent { name: "fspick",                       nargs: 3 },
+    Sysent { name: "fstat",                        nargs: 2 },
+    Sysent { name: "fstat64",                      nargs: 2 },
+    Sysent { name: "fstatat64",                    nargs: 4 },
+    Sysent { name: "fstatfs",                      nargs: 2 },
+    Sysent { name: "fstatfs64",                    nargs: 3 },
+    Sysent { name: "fsync",                        nargs: 1 },
+    Sysent { name: "ftime",                        nargs: 0 },
+    Sysent { name: "ftruncate",                    nargs: 2 },
+    Sysent { name: "ftruncate64",                  nargs: 2 },
+    Sysent { name: "futex",                        nargs: 6 },
+    Sysent { name: "futex_requeue",                nargs: 4 },
+    Sysent { name: "futex_time64",                 nargs: 6 },
+    Sysent { name: "futex_wait",                   nargs: 6 },
+    Sysent { name: "futex_waitv",                  nargs: 5 },
+    Sysent { name: "futex_wake",                   nargs: 4 },
+    Sysent { name: "futimesat",                    nargs: 3 },
+    Sysent { name: "get_kernel_syms",              nargs: 1 },
+    Sysent { name: "get_mempolicy",                nargs: 5 },
+    Sysent { name: "get_robust_list",              nargs: 3 },
+    Sysent { name: "get_thread_area",              nargs: 1 },
+    Sysent { name: "getcpu",                       nargs: 3 },
+    Sysent { name: "getcwd",                       nargs: 2 },
+    Sysent { name: "getdents",                     nargs: 3 },
+    Sysent { name: "getdents64",                   nargs: 3 },
+    Sysent { name: "getegid",                      nargs: 0 },
+    Sysent { name: "getegid32",                    nargs: 0 },
+    Sysent { name: "geteuid",                      nargs: 0 },
+    Sysent { name: "geteuid32",                    nargs: 0 },
+    Sysent { name: "getgid",                       nargs: 0 },
+    Sysent { name: "getgid32",                     nargs: 0 },
+    Sysent { name: "getgroups",                    nargs: 2 },
+    Sysent { name: "getgroups32",                  nargs: 2 },
+    Sysent { name: "getitimer",                    nargs: 2 },
+    Sysent { name: "getpeername",                  nargs: 3 },
+    Sysent { name: "getpgid",                      nargs: 1 },
+    Sysent { name: "getpgrp",                      nargs: 0 },
+    Sysent { name: "getpid",                       nargs: 0 },
+    Sysent { name: "getpmsg",                      nargs: 5 },
+    Sysent { name: "getppid",                      nargs: 0 },
+    Sysent { name: "getpriority",                  nargs: 2 },
+    Sysent { name: "getrandom",                    nargs: 3 },
+    Sysent { name: "getresgid",                    nargs: 3 },
+    Sysent { name: "getresgid32",                  nargs: 3 },
+    Sysent { name: "getresuid",                    nargs: 3 },
+    Sysent { name: "getresuid32",                  nargs: 3 },
+    Sysent { name: "getrlimit",                    nargs: 2 },
+    Sysent { name: "getrusage",                    nargs: 2 },
+    Sysent { name: "getsid",                       nargs: 1 },
+    Sysent { name: "getsockname",                  nargs: 3 },
+    Sysent { name: "getsockopt",                   nargs: 5 },
+    Sysent { name: "gettid",                       nargs: 0 },
+    Sysent { name: "gettimeofday",                 nargs: 2 },
+    Sysent { name: "getuid",                       nargs: 0 },
+    Sysent { name: "getuid32",                     nargs: 0 },
+    Sysent { name: "getxattr",                     nargs: 4 },
+    Sysent { name: "getxattrat",                   nargs: 6 },
+    Sysent { name: "gtty",                         nargs: 0 },
+    Sysent { name: "idle",                         nargs: 0 },
+    Sysent { name: "init_module",                  nargs: 3 },
+    Sysent { name: "inotify_add_watch",            nargs: 3 },
+    Sysent { name: "inotify_init",                 nargs: 0 },
+    Sysent { name: "inotify_init1",                nargs: 1 },
+    Sysent { name: "inotify_rm_watch",             nargs: 2 },
+    Sysent { name: "io_cancel",                    nargs: 3 },
+    Sysent { name: "io_destroy",                   nargs: 1 },
+    Sysent { name: "io_getevents",                 nargs: 5 },
+    Sysent { name: "io_pgetevents",                nargs: 6 },
+    Sysent { name: "io_pgetevents_time64",         nargs: 6 },
+    Sysent { name: "io_setup",                     nargs: 2 },
+    Sysent { name: "io_submit",                    nargs: 3 },
+    Sysent { name: "io_uring_enter",               nargs: 6 },
+    Sysent { name: "io_uring_register",            nargs: 4 },
+    Sysent { name: "io_uring_setup",               nargs: 2 },
+    Sysent { name: "ioctl",                        nargs: 3 },
+    Sysent { name: "ioperm",                       nargs: 3 },
+    Sysent { name: "iopl",                         nargs: 1 },
+    Sysent { name: "ioprio_get",                   nargs: 2 },
+    Sysent { name: "ioprio_set",                   nargs: 3 },
+    Sysent { name: "ipc",                          nargs: 6 },
+    Sysent { name: "kcmp",                         nargs: 5 },
+    Sysent { name: "kexec_file_load",              nargs: 5 },
+    Sysent { name: "kexec_load",                   nargs: 4 },
+    Sysent { name: "keyctl",                       nargs: 5 },
+    Sysent { name: "kill",                         nargs: 2 },
+    Sysent { name: "landlock_add_rule",            nargs: 4 },
+    Sysent { name: "landlock_create_ruleset",      nargs: 3 },
+    Sysent { name: "landlock_restrict_self",       nargs: 2 },
+    Sysent { name: "lchown",                       nargs: 3 },
+    Sysent { name: "lchown32",                     nargs: 3 },
+    Sysent { name: "lgetxattr",                    nargs: 4 },
+    Sysent { name: "link",                         nargs: 2 },
+    Sysent { name: "linkat",                       nargs: 5 },
+    Sysent { name: "listen",                       nargs: 2 },
+    Sysent { name: "listmount",                    nargs: 4 },
+    Sysent { name: "listxattr",                    nargs: 3 },
+    Sysent { name: "listxattrat",                  nargs: 5 },
+    Sysent { name: "llistxattr",                   nargs: 3 },
+    Sysent { name: "llseek",                       nargs: 5 },
+    Sysent { name: "lock",                         nargs: 0 },
+    Sysent { name: "lookup_dcookie",               nargs: 3 },
+    Sysent { name: "lremovexattr",                 nargs: 2 },
+    Sysent { name: "lseek",                        nargs: 3 },
+    Sysent { name: "lsetxattr",                    nargs: 5 },
+    Sysent { name: "lsm_get_self_attr",            nargs: 4 },
+    Sysent { name: "lsm_list_modules",             nargs: 3 },
+    Sysent { name: "lsm_set_self_attr",            nargs: 4 },
+    Sysent { name: "lstat",                        nargs: 2 },
+    Sysent { name: "lstat64",                      nargs: 2 },
+    Sysent { name: "madvise",                      nargs: 3 },
+    Sysent { name: "map_shadow_stack",             nargs: 3 },
+    Sysent { name: "mbind",                        nargs: 6 },
+    Sysent { name: "membarrier",                   nargs: 3 },
+    Sysent { name: "memfd_create",                 nargs: 2 },
+    Sysent { name: "memfd_secret",                 nargs: 1 },
+    Sysent { name: "migrate_pages",                nargs: 4 },
+    Sysent { name: "mincore",                      nargs: 3 },
+    Sysent { name: "mkdir",                        nargs: 2 },
+    Sysent { name: "mkdirat",                      nargs: 3 },
+    Sysent { name: "mknod",                        nargs: 3 },
+    Sysent { name: "mknodat",                      nargs: 4 },
+    Sysent { name: "mlock",                        nargs: 2 },
+    Sysent { name: "mlock2",                       nargs: 3 },
+    Sysent { name: "mlockall",                     nargs: 1 },
+    Sysent { name: "mmap",                         nargs: 6 },
+    Sysent { name: "mmap2",                        nargs: 6 },
+    Sysent { name: "modify_ldt",                   nargs: 3 },
+    Sysent { name: "mount",                        nargs: 5 },
+    Sysent { name: "mount_setattr",                nargs: 5 },
+    Sysent { name: "move_mount",                   nargs: 5 },
+    Sysent { name: "move_pages",                   nargs: 6 },
+    Sysent { name: "mprotect",                     nargs: 3 },
+    Sysent { name: "mpx",                          nargs: 0 },
+    Sysent { name: "mq_getsetattr",                nargs: 3 },
+    Sysent { name: "mq_notify",                    nargs: 2 },
+    Sysent { name: "mq_open",                      nargs: 4 },
+    Sysent { name: "mq_timedreceive",              nargs: 5 },
+    Sysent { name: "mq_timedreceive_time64",       nargs: 5 },
+    Sysent { name: "mq_timedsend",                 nargs: 5 },
+    Sysent { name: "mq_timedsend_time64",          nargs: 5 },
+    Sysent { name: "mq_unlink",                    nargs: 1 },
+    Sysent { name: "mremap",                       nargs: 5 },
+    Sysent { name: "mseal",                        nargs: 3 },
+    Sysent { name: "msgctl",                       nargs: 3 },
+    Sysent { name: "msgget",                       nargs: 2 },
+    Sysent { name: "msgrcv",                       nargs: 5 },
+    Sysent { name: "msgsnd",                       nargs: 4 },
+    Sysent { name: "msync",                        nargs: 3 },
+    Sysent { name: "munlock",                      nargs: 2 },
+    Sysent { name: "munlockall",                   nargs: 0 },
+    Sysent { name: "munmap",                       nargs: 2 },
+    Sysent { name: "name_to_handle_at",            nargs: 5 },
+    Sysent { name: "nanosleep",                    nargs: 2 },
+    Sysent { name: "newfstatat",                   nargs: 4 },
+    Sysent { name: "nfsservctl",                   nargs: 3 },
+    Sysent { name: "nice",                         nargs: 1 },
+    Sysent { name: "old_mmap",                     nargs: 1 },
+    Sysent { name: "oldfstat",                     nargs: 2 },
+    Sysent { name: "oldlstat",                     nargs: 2 },
+    Sysent { name: "oldolduname",                  nargs: 1 },
+    Sysent { name: "oldstat",                      nargs: 2 },
+    Sysent { name: "olduname",                     nargs: 1 },
+    Sysent { name: "open",                         nargs: 3 },
+    Sysent { name: "open_by_handle_at",            nargs: 3 },
+    Sysent { name: "open_tree",                    nargs: 3 },
+    Sysent { name: "open_tree_attr",               nargs: 5 },
+    Sysent { name: "openat",                       nargs: 4 },
+    Sysent { name: "openat2",                      nargs: 4 },
+    Sysent { name: "pause",                        nargs: 0 },
+    Sysent { name: "perf_event_open",              nargs: 5 },
+    Sysent { name: "personality",                  nargs: 1 },
+    Sysent { name: "pidfd_getfd",                  nargs: 3 },
+    Sysent { name: "pidfd_open",                   nargs: 2 },
+    Sysent { name: "pidfd_send_signal",            nargs: 4 },
+    Sysent { name: "pipe",                         nargs: 1 },
+    Sysent { name: "pipe2",                        nargs: 2 },
+    Sysent { name: "pivot_root",                   nargs: 2 },
+    Sysent { name: "pkey_alloc",                   nargs: 2 },
+    Sysent { name: "pkey_free",                    nargs: 1 },
+    Sysent { name: "pkey_mprotect",                nargs: 4 },
+    Sysent { name: "poll",                         nargs: 3 },
+    Sysent { name: "ppoll",                        nargs: 5 },
+    Sysent { name: "ppoll_time64",                 nargs: 5 },
+    Sysent { name: "prctl",                        nargs: 5 },
+    Sysent { name: "pread64",                      nargs: 4 },
+    Sysent { name: "preadv",                       nargs: 5 },
+    Sysent { name: "preadv2",                      nargs: 6 },
+    Sysent { name: "prlimit64",                    nargs: 4 },
+    Sysent { name: "process_madvise",              nargs: 5 },
+    Sysent { name: "process_mrelease",             nargs: 2 },
+    Sysent { name: "process_vm_readv",             nargs: 6 },
+    Sysent { name: "process_vm_writev",            nargs: 6 },
+    Sysent { name: "prof",                         nargs: 0 },
+    Sysent { name: "profil",                       nargs: 0 },
+    Sysent { name: "pselect6",                     nargs: 6 },
+    Sysent { name: "pselect6_time64",              nargs: 6 },
+    Sysent { name: "ptrace",                       nargs: 4 },
+    Sysent { name: "putpmsg",                      nargs: 5 },
+    Sysent { name: "pwrite64",                     nargs: 4 },
+    Sysent { name: "pwritev",                      nargs: 5 },
+    Sysent { name: "pwritev2",                     nargs: 6 },
+    Sysent { name: "query_module",                 nargs: 5 },
+    Sysent { name: "quotactl",                     nargs: 4 },
+    Sysent { name: "quotactl_fd",                  nargs: 4 },
+    Sysent { name: "read",                         nargs: 3 },
+    Sysent { name: "readahead",                    nargs: 3 },
+    Sysent { name: "readdir",                      nargs: 3 },
+    Sysent { name: "readlink",                     nargs: 3 },
+    Sysent { name: "readlinkat",                   nargs: 4 },
+    Sysent { name: "readv",                        nargs: 3 },
+    Sysent { name: "reboot",                       nargs: 4 },
+    Sysent { name: "recvfrom",                     nargs: 6 },
+    Sysent { name: "recvmmsg",                     nargs: 5 },
+    Sysent { name: "recvmmsg_time64",              nargs: 5 },
+    Sysent { name: "recvmsg",                      nargs: 3 },
+    Sysent { name: "remap_file_pages",             nargs: 5 },
+    Sysent { name: "removexattr",                  nargs: 2 },
+    Sysent { name: "removexattrat",                nargs: 4 },
+    Sysent { name: "rename",                       nargs: 2 },
+    Sysent { name: "renameat",                     nargs: 4 },
+    Sysent { name: "renameat2",                    nargs: 5 },
+    Sysent { name: "request_key",                  nargs: 4 },
+    Sysent { name: "restart_syscall",              nargs: 0 },
+    Sysent { name: "riscv_flush_icache",           nargs: 3 },
+    Sysent { name: "riscv_hwprobe",                nargs: 5 },
+    Sysent { name: "rmdir",                        nargs: 1 },
+    Sysent { name: "rseq",                         nargs: 4 },
+    Sysent { name: "rt_sigaction",                 nargs: 4 },
+    Sysent { name: "rt_sigpending",                nargs: 2 },
+    Sysent { name: "rt_sigprocmask",               nargs: 4 },
+    Sysent { name: "rt_sigqueueinfo",              nargs: 3 },
+    Sysent { name: "rt_sigreturn",                 nargs: 0 },
+    Sysent { name: "rt_sigsuspend",                nargs: 2 },
+    Sysent { name: "rt_sigtimedwait",              nargs: 4 },
+    Sysent { name: "rt_sigtimedwait_time64",       nargs: 4 },
+    Sysent { name: "rt_tgsigqueueinfo",            nargs: 4 },
+    Sysent { name: "sched_get_priority_max",       nargs: 1 },
+    Sysent { name: "sched_get_priority_min",       nargs: 1 },
+    Sysent { name: "sched_getaffinity",            nargs: 3 },
+    Sysent { name: "sched_getattr",                nargs: 4 },
+    Sysent { name: "sched_getparam",               nargs: 2 },
+    Sysent { name: "sched_getscheduler",           nargs: 1 },
+    Sysent { name: "sched_rr_get_interval",        nargs: 2 },
+    Sysent { name: "sched_rr_get_interval_time64", nargs: 2 },
+    Sysent { name: "sched_setaffinity",            nargs: 3 },
+    Sysent { name: "sched_setattr",                nargs: 3 },
+    Sysent { name: "sched_setparam",               nargs: 2 },
+    Sysent { name: "sched_setscheduler",           nargs: 3 },
+    Sysent { name: "sched_yield",                  nargs: 0 },
+    Sysent { name: "seccomp",                      nargs: 3 },
+    Sysent { name: "security",                     nargs: 0 },
+    Sysent { name: "select",                       nargs: 5 },
+    Sysent { name: "semctl",                       nargs: 4 },
+    Sysent { name: "semget",                       nargs: 3 },
+    Sysent { name: "semop",                        nargs: 3 },
+    Sysent { name: "semtimedop",                   nargs: 4 },
+    Sysent { name: "semtimedop_time64",            nargs: 4 },
+    Sysent { name: "sendfile",                     nargs: 4 },
+    Sysent { name: "sendfile64",                   nargs: 4 },
+    Sysent { name: "sendmmsg",                     nargs: 4 },
+    Sysent { name: "sendmsg",                      nargs: 3 },
+    Sysent { name: "sendto",                       nargs: 6 },
+    Sysent { name: "set_mempolicy",                nargs: 3 },
+    Sysent { name: "set_mempolicy_home_node",      nargs: 4 },
+    Sysent { name: "set_robust_list",              nargs: 2 },
+    Sysent { name: "set_thread_area",              nargs: 1 },
+    Sysent { name: "set_tid_address",              nargs: 1 },
+    Sysent { name: "setdomainname",                nargs: 2 },
+    Sysent { name: "setfsgid",                     nargs: 1 },
+    Sysent { name: "setfsgid32",                   nargs: 1 },
+    Sysent { name: "setfsuid",                     nargs: 1 },
+    Sysent { name: "setfsuid32",                   nargs: 1 },
+    Sysent { name: "setgid",                       nargs: 1 },
+    Sysent { name: "setgid32",                     nargs: 1 },
+    Sysent { name: "setgroups",                    nargs: 2 },
+    Sysent { name: "setgroups32",                  nargs: 2 },
+    Sysent { name: "sethostname",                  nargs: 2 },
+    Sysent { name: "setitimer",                    nargs: 3 },
+    Sysent { name: "setns",                        nargs: 2 },
+    Sysent { name: "setpgid",                      nargs: 2 },
+    Sysent { name: "setpriority",                  nargs: 3 },
+    Sysent { name: "setregid",                     nargs: 2 },
+    Sysent { name: "setregid32",                   nargs: 2 },
+    Sysent { name: "setresgid",                    nargs: 3 },
+    Sysent { name: "setresgid32",                  nargs: 3 },
+    Sysent { name: "setresuid",                    nargs: 3 },
+    Sysent { name: "setresuid32",                  nargs: 3 },
+    Sysent { name: "setreuid",                     nargs: 2 },
+    Sysent { name: "setreuid32",                   nargs: 2 },
+    Sysent { name: "setrlimit",                    nargs: 2 },
+    Sysent { name: "setsid",                       nargs: 0 },
+    Sysent { name: "setsockopt",                   nargs: 5 },
+    Sysent { name: "settimeofday",                 nargs: 2 },
+    Sysent { name: "setuid",                       nargs: 1 },
+    Sysent { name: "setuid32",                     nargs: 1 },
+    Sysent { name: "setxattr",                     nargs: 5 },
+    Sysent { name: "setxattrat",                   nargs: 6 },
+    Sysent { name: "sgetmask",                     nargs: 0 },
+    Sysent { name: "shmat",                        nargs: 3 },
+    Sysent { name: "shmctl",                       nargs: 3 },
+    Sysent { name: "shmdt",                        nargs: 1 },
+    Sysent { name: "shmget",                       nargs: 3 },
+    Sysent { name: "shutdown",                     nargs: 2 },
+    Sysent { name: "sigaction",                    nargs: 3 },
+    Sysent { name: "sigaltstack",                  nargs: 2 },
+    Sysent { name: "signal",                       nargs: 2 },
+    Sysent { name: "signalfd",                     nargs: 3 },
+    Sysent { name: "signalfd4",                    nargs: 4 },
+    Sysent { name: "sigpending",                   nargs: 1 },
+    Sysent { name: "sigprocmask",                  nargs: 3 },
+    Sysent { name: "sigreturn",                    nargs: 0 },
+    Sysent { name: "sigsuspend",                   nargs: 3 },
+    Sysent { name: "socket",                       nargs: 3 },
+    Sysent { name: "socketcall",                   nargs: 2 },
+    Sysent { name: "socketpair",                   nargs: 4 },
+    Sysent { name: "splice",                       nargs: 6 },
+    Sysent { name: "ssetmask",                     nargs: 1 },
+    Sysent { name: "stat",                         nargs: 2 },
+    Sysent { name: "stat64",                       nargs: 2 },
+    Sysent { name: "statfs",                       nargs: 2 },
+    Sysent { name: "statfs64",                     nargs: 3 },
+    Sysent { name: "statmount",                    nargs: 4 },
+    Sysent { name: "statx",                        nargs: 5 },
+    Sysent { name: "stime",                        nargs: 1 },
+    Sysent { name: "stty",                         nargs: 0 },
+    Sysent { name: "swapoff",                      nargs: 1 },
+    Sysent { name: "swapon",                       nargs: 2 },
+    Sysent { name: "symlink",                      nargs: 2 },
+    Sysent { name: "symlinkat",                    nargs: 3 },
+    Sysent { name: "sync",                         nargs: 0 },
+    Sysent { name: "sync_file_range",              nargs: 4 },
+    Sysent { name: "sync_file_range2",             nargs: 4 },
+    Sysent { name: "syncfs",                       nargs: 1 },
+    Sysent { name: "sysfs",                        nargs: 3 },
+    Sysent { name: "sysinfo",                      nargs: 1 },
+    Sysent { name: "syslog",                       nargs: 3 },
+    Sysent { name: "tee",                          nargs: 4 },
+    Sysent { name: "tgkill",                       nargs: 3 },
+    Sysent { name: "time",                         nargs: 1 },
+    Sysent { name: "timer_create",                 nargs: 3 },
+    Sysent { name: "timer_delete",                 nargs: 1 },
+    Sysent { name: "timer_getoverrun",             nargs: 1 },
+    Sysent { name: "timer_gettime",                nargs: 2 },
+    Sysent { name: "timer_gettime64",              nargs: 2 },
+    Sysent { name: "timer_settime",                nargs: 4 },
+    Sysent { name: "timer_settime64",              nargs: 4 },
+    Sysent { name: "timerfd_create",               nargs: 2 },
+    Sysent { name: "timerfd_gettime",              nargs: 2 },
+    Sysent { name: "timerfd_gettime64",            nargs: 2 },
+    Sysent { name: "timerfd_settime",              nargs: 4 },
+    Sysent { name: "timerfd_settime64",            nargs: 4 },
+    Sysent { name: "times",                        nargs: 1 },
+    Sysent { name: "tkill",                        nargs: 2 },
+    Sysent { name: "truncate",                     nargs: 2 },
+    Sysent { name: "truncate64",                   nargs: 2 },
+    Sysent { name: "tuxcall",                      nargs: 0 },
+    Sysent { name: "ugetrlimit",                   nargs: 2 },
+    Sysent { name: "ulimit",                       nargs: 0 },
+    Sysent { name: "umask",                        nargs: 1 },
+    Sysent { name: "umount",                       nargs: 1 },
+    Sysent { name: "umount2",                      nargs: 2 },
+    Sysent { name: "uname",                        nargs: 1 },
+    Sysent { name: "unlink",                       nargs: 1 },
+    Sysent { name: "unlinkat",                     nargs: 3 },
+    Sysent { name: "unshare",                      nargs: 1 },
+    Sysent { name: "uretprobe",                    nargs: 0 },
+    Sysent { name: "uselib",                       nargs: 1 },
+    Sysent { name: "userfaultfd",                  nargs: 1 },
+    Sysent { name: "ustat",                        nargs: 2 },
+    Sysent { name: "utime",                        nargs: 2 },
+    Sysent { name: "utimensat",                    nargs: 4 },
+    Sysent { name: "utimensat_time64",             nargs: 4 },
+    Sysent { name: "utimes",                       nargs: 2 },
+    Sysent { name: "vfork",                        nargs: 0 },
+    Sysent { name: "vhangup",                      nargs: 0 },
+    Sysent { name: "vm86",                         nargs: 2 },
+    Sysent { name: "vm86old",                      nargs: 1 },
+    Sysent { name: "vmsplice",                     nargs: 4 },
+    Sysent { name: "vserver",                      nargs: 0 },
+    Sysent { name: "wait4",                        nargs: 4 },
+    Sysent { name: "waitid",                       nargs: 5 },
+    Sysent { name: "waitpid",                      nargs: 3 },
+    Sysent { name: "write",                        nargs: 3 },
+    Sysent { name: "writev",                       nargs: 3 },
+];